@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use aoc24::{days, Day, Progress, Puzzle};
+
+use crate::{guard, load, solve, Outcome};
+
+// Timings of every run of one part, sorted.
+struct Timings {
+    load: Vec<Duration>,
+    parse: Vec<Duration>,
+    solve: Vec<Duration>,
+}
+
+// Nearest-rank percentile of sorted durations.
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn time_part(day: &Day, part: u8, runs: u32) -> Result<Timings, Outcome> {
+    let mut timings = Timings {
+        load: vec![],
+        parse: vec![],
+        solve: vec![],
+    };
+    for _ in 0..runs {
+        let start = Instant::now();
+        let input = load(day.number, part, &Puzzle::Puzzle)?;
+        timings.load.push(start.elapsed());
+
+        let start = Instant::now();
+        let parsed = guard(|| day.solutions.parse(&input))?;
+        timings.parse.push(start.elapsed());
+
+        let start = Instant::now();
+        let outcome = solve(day.solutions, part, parsed.as_ref(), &Progress::hidden());
+        timings.solve.push(start.elapsed());
+        if !matches!(outcome, Outcome::Solved(_)) {
+            return Err(outcome);
+        }
+    }
+    timings.load.sort();
+    timings.parse.sort();
+    timings.solve.sort();
+    Ok(timings)
+}
+
+/// Runs every selected part `runs` times on the puzzle input and prints the min, median
+/// and p95 of the solve time next to the median time spent loading and parsing the input.
+pub fn bench(day: Option<usize>, part: Option<u8>, runs: u32) -> bool {
+    let parts = match part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let selected = days()
+        .iter()
+        .filter(|registered| day.is_none_or(|day| registered.number == day))
+        .collect::<Vec<_>>();
+    if selected.is_empty() {
+        println!("Day {} is not implemented yet.", day.unwrap_or_default());
+        return true;
+    }
+
+    let separator =
+        "+-----+------+------------+------------+------------+------------+------------+";
+    println!("{}", separator);
+    println!(
+        "| Day | Part | {:>10} | {:>10} | {:>10} | {:>10} | {:>10} |",
+        "Min", "Median", "P95", "Parse", "Load"
+    );
+    println!("{}", separator);
+    let mut success = true;
+    for day in selected {
+        for &part in parts.iter() {
+            match time_part(day, part, runs) {
+                Ok(timings) => println!(
+                    "| {:>3} | {:>4} | {:>10} | {:>10} | {:>10} | {:>10} | {:>10} |",
+                    day.number,
+                    part,
+                    format!("{:.2?}", timings.solve[0]),
+                    format!("{:.2?}", percentile(&timings.solve, 50)),
+                    format!("{:.2?}", percentile(&timings.solve, 95)),
+                    format!("{:.2?}", percentile(&timings.parse, 50)),
+                    format!("{:.2?}", percentile(&timings.load, 50)),
+                ),
+                Err(outcome) => {
+                    if !matches!(outcome, Outcome::NotImplemented) {
+                        success = false;
+                    }
+                    println!(
+                        "| {:>3} | {:>4} | {:<64} |",
+                        day.number,
+                        part,
+                        outcome.describe().chars().take(64).collect::<String>()
+                    );
+                }
+            }
+        }
+    }
+    println!("{}", separator);
+    success
+}
@@ -1,37 +1,37 @@
-use itertools::Itertools;
-use std::{collections::HashMap, fs};
+use std::collections::HashMap;
 
-use crate::{DaySolutions, Puzzle};
+use crate::{parse::n_words, Answer, AocError, DaySolutions, Progress};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        Puzzle::Example => fs::read_to_string("src/day1/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day1/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = 1;
 
-fn read_lists(pb: String) -> (Vec<i32>, Vec<i32>) {
+fn read_lists(pb: &str) -> Result<(Vec<i32>, Vec<i32>), AocError> {
     let mut l_list: Vec<i32> = vec![];
     let mut r_list: Vec<i32> = vec![];
     for line in pb.lines() {
-        let [l_ele, r_ele] = &line.split_whitespace().collect_vec()[..2] else {
-            return (vec![], vec![]);
-        };
-
-        l_list.push(l_ele.parse().unwrap());
-        r_list.push(r_ele.parse().unwrap());
+        let [l_ele, r_ele] = n_words(DAY, pb, line, "two location IDs")?;
+        l_list.push(l_ele);
+        r_list.push(r_ele);
     }
-    (l_list, r_list)
+    Ok((l_list, r_list))
 }
 
 impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        let pb_string = load_problem(puzzle);
-        let (mut l_list, mut r_list) = read_lists(pb_string);
+    type Parsed = (Vec<i32>, Vec<i32>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        read_lists(input)
+    }
+
+    fn part1(
+        &self,
+        (l_list, r_list): &Self::Parsed,
+        _progress: &Progress,
+    ) -> Result<Answer, AocError> {
+        let mut l_list = l_list.clone();
+        let mut r_list = r_list.clone();
 
         r_list.sort();
         l_list.sort();
@@ -40,12 +40,14 @@ impl DaySolutions for Problem {
             delta_list.push((r_list[i] - l_list[i]).abs());
         }
         let result: i32 = delta_list.iter().sum();
-        format!("{result}")
+        Ok(result.into())
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        let pb_string = load_problem(puzzle);
-        let (l_list, r_list) = read_lists(pb_string);
+    fn part2(
+        &self,
+        (l_list, r_list): &Self::Parsed,
+        _progress: &Progress,
+    ) -> Result<Answer, AocError> {
         let mut counter: HashMap<i32, i32> = l_list.iter().map(|e| (*e, 0i32)).collect();
         for e in r_list.iter() {
             counter.entry(*e).and_modify(|k| *k += 1);
@@ -56,6 +58,8 @@ impl DaySolutions for Problem {
                 result += k * v
             }
         }
-        format!("{result}")
+        Ok(result.into())
     }
 }
+
+crate::example_tests!(1);
@@ -1,127 +1,74 @@
-use std::{collections::HashSet, fs};
+use std::collections::HashSet;
 
-use itertools::Itertools;
-
-use crate::{DaySolutions, Puzzle};
+use crate::{geometry::Vec2, grid::Grid, Answer, AocError, DaySolutions, Progress};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        //TODO: change the directory name
-        Puzzle::Example => fs::read_to_string("src/day10/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day10/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = 10;
 
-fn parse_pb(input: &str) -> Vec<Vec<char>> {
-    input
-        .lines()
-        .map(|line| line.chars().collect_vec())
-        .collect_vec()
+fn parse_pb(input: &str) -> Result<Grid<u32>, AocError> {
+    Grid::parse(DAY, input, |c| {
+        c.to_digit(10)
+            .ok_or_else(|| format!("expected a height, found {:?}", c))
+    })
 }
 
-fn trailheads(pb: &Vec<Vec<char>>) -> Vec<(usize, usize)> {
-    pb.iter()
-        .enumerate()
-        .map(|(i, line)| {
-            line.iter()
-                .enumerate()
-                .filter(|(_, c)| **c == '0')
-                .map(|(j, _)| (i, j))
-                .collect_vec()
-        })
-        .flatten()
-        .collect_vec()
+fn trailheads(pb: &Grid<u32>) -> Vec<Vec2> {
+    pb.find_all(&0).collect()
 }
 
-fn next_step(pb: &Vec<Vec<char>>, pos: &(usize, usize)) -> HashSet<(usize, usize)> {
-    let (i, j) = *pos;
-    let u_min = if i == 0 { i } else { i - 1 };
-    let v_min = if j == 0 { j } else { j - 1 };
-    let u_max = if i == pb.len() - 1 { i } else { i + 1 };
-    let v_max = if j == pb.first().unwrap().len() - 1 {
-        j
-    } else {
-        j + 1
-    };
-    let next_height = pb[i][j].to_digit(10).unwrap() + 1;
-    let mut next_nodes: HashSet<(usize, usize)> = HashSet::new();
-    for u in u_min..=u_max {
-        for v in v_min..=v_max {
-            if u == i || v == j {
-                if pb[u][v].to_digit(10).unwrap() == next_height {
-                    next_nodes.insert((u, v));
-                }
-            }
-        }
-    }
-
-    next_nodes
+fn next_step(pb: &Grid<u32>, pos: &Vec2) -> HashSet<Vec2> {
+    let next_height = pb[*pos] + 1;
+    pb.neighbors4(*pos)
+        .filter(|next| pb[*next] == next_height)
+        .collect()
 }
-fn score_trailhead(pb: &Vec<Vec<char>>, zero: &(usize, usize)) -> usize {
+fn score_trailhead(pb: &Grid<u32>, zero: &Vec2) -> usize {
     let mut current_nodes = next_step(pb, zero);
     for _ in 1..9 {
         current_nodes = current_nodes
             .iter()
-            .map(|node| next_step(pb, node))
-            .flatten()
+            .flat_map(|node| next_step(pb, node))
             .collect();
     }
     current_nodes.len()
 }
 
-fn next_step_2(pb: &Vec<Vec<char>>, pos: &(usize, usize)) -> Vec<(usize, usize)> {
-    let (i, j) = *pos;
-    let u_min = if i == 0 { i } else { i - 1 };
-    let v_min = if j == 0 { j } else { j - 1 };
-    let u_max = if i == pb.len() - 1 { i } else { i + 1 };
-    let v_max = if j == pb.first().unwrap().len() - 1 {
-        j
-    } else {
-        j + 1
-    };
-    let next_height = pb[i][j].to_digit(10).unwrap() + 1;
-    let mut next_nodes = vec![];
-    for u in u_min..=u_max {
-        for v in v_min..=v_max {
-            if u == i || v == j {
-                if pb[u][v].to_digit(10).unwrap() == next_height {
-                    next_nodes.push((u, v));
-                }
-            }
-        }
-    }
-
-    next_nodes
+fn next_step_2(pb: &Grid<u32>, pos: &Vec2) -> Vec<Vec2> {
+    let next_height = pb[*pos] + 1;
+    pb.neighbors4(*pos)
+        .filter(|next| pb[*next] == next_height)
+        .collect()
 }
-fn score_trailhead_2(pb: &Vec<Vec<char>>, zero: &(usize, usize)) -> usize {
+fn score_trailhead_2(pb: &Grid<u32>, zero: &Vec2) -> usize {
     let mut current_nodes = next_step_2(pb, zero);
     for _ in 1..9 {
         current_nodes = current_nodes
             .iter()
-            .map(|node| next_step_2(pb, node))
-            .flatten()
+            .flat_map(|node| next_step_2(pb, node))
             .collect();
     }
     current_nodes.len()
 }
 impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let pb = parse_pb(&input);
-        let zeros = trailheads(&pb);
-        let result: usize = zeros.iter().map(|z| score_trailhead(&pb, z)).sum();
-        format!("{:?}", result)
+    type Parsed = Grid<u32>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_pb(input)
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let pb = parse_pb(&input);
-        let zeros = trailheads(&pb);
-        let result: usize = zeros.iter().map(|z| score_trailhead_2(&pb, z)).sum();
-        format!("{:?}", result)
+    fn part1(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
+        let zeros = trailheads(pb);
+        let result: usize = zeros.iter().map(|z| score_trailhead(pb, z)).sum();
+        Ok(result.into())
+    }
+
+    fn part2(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
+        let zeros = trailheads(pb);
+        let result: usize = zeros.iter().map(|z| score_trailhead_2(pb, z)).sum();
+        Ok(result.into())
     }
 }
+
+crate::example_tests!(10);
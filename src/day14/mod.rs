@@ -1,98 +1,118 @@
-use std::{fs, isize, usize};
-
 use itertools::Itertools;
-use regex::Regex;
 
-use crate::{DaySolutions, Puzzle};
+use crate::{geometry::Vec2, grid::Grid, parse::n_ints, Answer, AocError, DaySolutions, Progress};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        Puzzle::Example => fs::read_to_string("src/day14/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day14/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = 14;
 
 #[derive(Debug, Clone, Default)]
-struct Robot {
-    x: isize,
-    y: isize,
-    dx: isize,
-    dy: isize,
+pub struct Robot {
+    position: Vec2,
+    velocity: Vec2,
 }
 
 impl Robot {
     fn tick_n(&mut self, n: usize) {
-        let n = n as isize;
-        self.x += n * self.dx;
-        self.y += n * self.dy;
+        self.position += self.velocity * n as isize;
     }
     fn on_grid(&mut self, length: usize, width: usize) {
-        let length = length as isize;
-        let width = width as isize;
-        self.x = self.x % length;
-        self.y = self.y % width;
-
-        if self.x < 0 {
-            self.x += length;
-        }
-        if self.y < 0 {
-            self.y += width;
-        }
+        self.position = Vec2::new(
+            self.position.x.rem_euclid(length as isize),
+            self.position.y.rem_euclid(width as isize),
+        );
     }
 }
 
-fn parse_pb(input: &str) -> Vec<Robot> {
-    let mut pb = vec![];
-    let re = Regex::new(r"\=(-*\d+)\,(-*\d+).+=(-*\d+)\,(-*\d+)").unwrap();
-    for (_, [nb1, nb2, nb3, nb4]) in re.captures_iter(input).map(|c| c.extract()) {
-        let robot = Robot {
-            x: nb1.parse().unwrap(),
-            y: nb2.parse().unwrap(),
-            dx: nb3.parse().unwrap(),
-            dy: nb4.parse().unwrap(),
-        };
-        pb.push(robot);
-    }
-    pb
+// The robots of the puzzle move in a space 101 tiles wide and 103 tiles tall.
+const PUZZLE_SPACE: (usize, usize) = (101, 103);
+
+#[derive(Debug, Clone)]
+pub struct Space {
+    robots: Vec<Robot>,
+    length: usize,
+    width: usize,
 }
 
-fn draw(pb: Vec<Robot>, length: usize, width: usize) -> String {
-    let mut grid: Vec<Vec<isize>> = (0..width)
-        .map(|_| (0..length).map(|_| 0).collect_vec())
-        .collect_vec();
-    for robot in pb.iter() {
-        let mut m_robot = robot.clone();
-        m_robot.on_grid(length, width);
-        grid[m_robot.y as usize][m_robot.x as usize] += 1;
-    }
-    grid.iter()
+// The robots, one per line, after an optional `space=<length>,<width>` line for a space
+// other than the puzzle's, as in the example.
+fn parse_pb(input: &str) -> Result<Space, AocError> {
+    let mut lines = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    let (length, width) = match lines.next_if(|line| line.starts_with("space=")) {
+        Some(line) => {
+            let [length, width] = n_ints(DAY, input, line, "a space like `space=11,7`")?;
+            if length == 0 || width == 0 {
+                return Err(AocError::at(DAY, input, line, "the space is empty"));
+            }
+            (length, width)
+        }
+        None => PUZZLE_SPACE,
+    };
+    let robots = lines
         .map(|line| {
-            line.iter()
-                .map(|v| match v {
-                    0 => ".".to_string(),
-                    k => k.to_string(),
-                })
-                .join("|")
+            let [x, y, dx, dy] = n_ints(DAY, input, line, "a robot like `p=0,4 v=3,-3`")?;
+            if !(0..length as isize).contains(&x) || !(0..width as isize).contains(&y) {
+                return Err(AocError::at(
+                    DAY,
+                    input,
+                    line,
+                    format!("the robot is outside the {}x{} space", length, width),
+                ));
+            }
+            Ok(Robot {
+                position: Vec2::new(x, y),
+                velocity: Vec2::new(dx, dy),
+            })
         })
-        .join("\n")
+        .collect::<Result<_, _>>()?;
+    Ok(Space {
+        robots,
+        length,
+        width,
+    })
 }
 
-impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        let mut length: usize = 11;
-        let mut width: usize = 7;
-        if puzzle == Puzzle::Puzzle {
-            length = 101;
-            width = 103;
+impl Space {
+    /// The robots after `seconds`, each tile showing how many of them stand on it.
+    pub fn draw(&self, seconds: usize) -> String {
+        let mut grid = Grid::new(self.length, self.width, 0);
+        for robot in self.robots.iter() {
+            let mut m_robot = robot.clone();
+            m_robot.tick_n(seconds);
+            m_robot.on_grid(self.length, self.width);
+            grid[m_robot.position] += 1;
         }
+        let drawing = grid
+            .rows()
+            .map(|line| {
+                line.iter()
+                    .map(|v| match v {
+                        0 => ".".to_string(),
+                        k => k.to_string(),
+                    })
+                    .join("|")
+            })
+            .join("\n");
+        drawing
+    }
+}
+
+impl DaySolutions for Problem {
+    type Parsed = Space;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_pb(input)
+    }
+
+    fn part1(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
+        let (length, width) = (pb.length, pb.width);
+        let mut pb = pb.robots.clone();
         let half_length = (length / 2) as isize;
         let half_width = (width / 2) as isize;
-        let input = load_problem(puzzle);
-        let mut pb = parse_pb(&input);
         pb.iter_mut().for_each(|robot| {
             (*robot).tick_n(100);
             (*robot).on_grid(length, width);
@@ -100,35 +120,36 @@ impl DaySolutions for Problem {
 
         let q1 = pb
             .iter()
-            .filter(|robot| robot.x < half_length && robot.y < half_width)
+            .filter(|robot| robot.position.x < half_length && robot.position.y < half_width)
             .count();
         let q2 = pb
             .iter()
-            .filter(|robot| robot.x < half_length && robot.y > half_width)
+            .filter(|robot| robot.position.x < half_length && robot.position.y > half_width)
             .count();
         let q3 = pb
             .iter()
-            .filter(|robot| robot.x > half_length && robot.y < half_width)
+            .filter(|robot| robot.position.x > half_length && robot.position.y < half_width)
             .count();
         let q4 = pb
             .iter()
-            .filter(|robot| robot.x > half_length && robot.y > half_width)
+            .filter(|robot| robot.position.x > half_length && robot.position.y > half_width)
             .count();
 
         let result = q1 * q2 * q3 * q4;
-        format!("{}", result)
+        Ok(result.into())
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        if puzzle == Puzzle::Example {
-            return format!("irrelevant");
+    fn part2(&self, space: &Self::Parsed, progress: &Progress) -> Result<Answer, AocError> {
+        // the tree only shows up in the puzzle space
+        let (length, width) = (space.length, space.width);
+        if (length, width) != PUZZLE_SPACE {
+            return Ok(Answer::NotApplicable);
         }
-        let length = 101;
-        let width = 103;
-        let input = load_problem(puzzle);
         let mut possible_iter = vec![];
+        let frames = progress.bar("day 14: frames", 10000);
         'k: for k in 0..10000 {
-            let mut pb = parse_pb(&input);
+            frames.inc(1);
+            let mut pb = space.robots.clone();
             pb.iter_mut().for_each(|robot| {
                 (*robot).tick_n(k);
                 (*robot).on_grid(length, width);
@@ -137,7 +158,7 @@ impl DaySolutions for Problem {
                 for j in 0..width {
                     if pb
                         .iter()
-                        .filter(|robot| robot.x == i as isize && robot.y == j as isize)
+                        .filter(|robot| robot.position == Vec2::new(i as isize, j as isize))
                         .count()
                         > 1
                     {
@@ -146,11 +167,28 @@ impl DaySolutions for Problem {
                 }
             }
 
-            let filename = format!("output/day14/{}.txt", k);
-            fs::write(filename, draw(pb, length, width)).unwrap();
             possible_iter.push(k);
         }
 
-        format!("{}", possible_iter.iter().join(" - "))
+        Ok(possible_iter.into())
+    }
+}
+
+crate::example_tests!(14);
+
+#[cfg(test)]
+mod space_tests {
+    use super::*;
+
+    #[test]
+    fn space_is_declared_not_guessed() {
+        let space = Problem.parse("p=0,4 v=3,-3\np=6,3 v=-1,-3\n").unwrap();
+        assert_eq!((space.length, space.width), PUZZLE_SPACE);
+        let space = Problem.parse("space=11,7\np=0,4 v=3,-3\n").unwrap();
+        assert_eq!((space.length, space.width), (11, 7));
+        assert!(matches!(
+            Problem.parse("space=11,7\np=0,4 v=3,-3\np=11,0 v=1,1\n"),
+            Err(AocError::Parse { line: 3, .. })
+        ));
     }
 }
@@ -1,29 +1,31 @@
-use core::panic;
 use std::{
+    collections::HashSet,
     fmt::{Debug, Display},
-    fs,
 };
 
-use itertools::Itertools;
-
-use crate::{DaySolutions, Puzzle};
+use crate::{
+    geometry::{Direction, Vec2},
+    grid::Grid,
+    parse::two_sections,
+    Answer, AocError, DaySolutions, Progress,
+};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        Puzzle::Example => fs::read_to_string("src/day15/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day15/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = 15;
+
+mod replay;
+pub use replay::Replay;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Element {
     Robot,
     Wall,
     Food,
+    // the two halves of a box in the wide warehouse
+    BoxLeft,
+    BoxRight,
     Empty,
 }
 
@@ -33,36 +35,31 @@ impl Display for Element {
     }
 }
 impl Element {
-    fn to_str(&self) -> &str {
+    fn to_str(self) -> &'static str {
         match self {
             Element::Robot => "@",
             Element::Wall => "#",
             Element::Food => "O",
+            Element::BoxLeft => "[",
+            Element::BoxRight => "]",
             Element::Empty => ".",
         }
     }
 }
 
 #[derive(Debug, Clone)]
-struct Grid {
-    width: usize,
-    length: usize,
-    map: Vec<Vec<Element>>,
-    moves: Vec<char>,
-    robot: (usize, usize),
+pub struct Warehouse {
+    map: Grid<Element>,
+    moves: Vec<Direction>,
+    robot: Vec2,
 }
-impl Display for Grid {
+impl Display for Warehouse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let output = self
-            .map
-            .iter()
-            .map(|line| line.iter().map(|elt| elt.to_str()).join(""))
-            .join("\n");
         write!(
             f,
             "{}\n\nNext moves: {}",
-            output,
-            match self.moves.last() {
+            self.map,
+            match self.moves.last().and_then(|a| a.to_char()) {
                 Some(a) => {
                     a.to_string()
                 }
@@ -74,135 +71,231 @@ impl Display for Grid {
     }
 }
 
-impl Grid {
-    fn parse_pb(input: &str) -> Self {
-        if let Some((map_str, moves_str)) = input.split_once("\n\n") {
-            let mut robot = (0, 0);
-            let map = map_str
-                .lines()
-                .enumerate()
-                .map(|(i, line)| {
-                    line.chars()
-                        .enumerate()
-                        .map(|(j, c)| match c {
-                            '#' => Element::Wall,
-                            '@' => {
-                                robot = (i, j);
-                                Element::Robot
-                            }
-                            'O' => Element::Food,
-                            '.' => Element::Empty,
-                            _ => panic!(),
-                        })
-                        .collect_vec()
-                })
-                .collect_vec();
-            let length = map_str.lines().count();
-            let width = map_str.lines().next().unwrap().len();
-
-            let moves = moves_str.chars().rev().filter(|c| *c != '\n').collect_vec();
-
-            return Grid {
-                length,
-                width,
-                map,
-                moves,
-                robot,
-            };
-        } else {
-            panic!("error parsing")
+impl Warehouse {
+    fn parse_pb(input: &str) -> Result<Self, AocError> {
+        let (map_str, moves_str) = two_sections(DAY, input, "the warehouse map and the moves")?;
+        let mut robots = 0;
+        let map = Grid::parse(DAY, map_str, |c| {
+            Ok(match c {
+                '#' => Element::Wall,
+                '@' => {
+                    robots += 1;
+                    if robots > 1 {
+                        return Err("there is more than one robot".to_string());
+                    }
+                    Element::Robot
+                }
+                'O' => Element::Food,
+                '.' => Element::Empty,
+                _ => return Err(format!("unexpected element {:?}", c)),
+            })
+        })?;
+        let Some(robot) = map.find(&Element::Robot) else {
+            return Err(AocError::invalid(DAY, "there is no robot in the warehouse"));
+        };
+        // moves never check the bounds, the walls around the warehouse stop the robot
+        let is_wall = |elt: &Element| *elt == Element::Wall;
+        let enclosed = map.row(0).iter().all(is_wall)
+            && map.row(map.height() - 1).iter().all(is_wall)
+            && map.column(0).all(is_wall)
+            && map.column(map.width() - 1).all(is_wall);
+        if !enclosed {
+            return Err(AocError::invalid(
+                DAY,
+                "the warehouse must be a rectangle surrounded by walls",
+            ));
         }
+
+        let mut moves = vec![];
+        for (offset, c) in moves_str.char_indices() {
+            match Direction::from_char(c) {
+                Some(direction) => moves.push(direction),
+                None if c.is_ascii_whitespace() => (),
+                _ => {
+                    return Err(AocError::at(
+                        DAY,
+                        input,
+                        &moves_str[offset..],
+                        format!("expected one of < v > ^, got {:?}", c),
+                    ))
+                }
+            }
+        }
+        moves.reverse();
+
+        Ok(Warehouse { map, moves, robot })
     }
 
-    fn next_move(&mut self) {
-        let to_move = match self.moves.pop() {
-            Some(c) => c,
-            None => return (),
-        };
+    // The part 2 warehouse, where everything but the robot is twice as wide.
+    pub fn widen(&self) -> Warehouse {
+        let mut map = Grid::new(self.map.width() * 2, self.map.height(), Element::Empty);
+        for (pos, elt) in self.map.iter() {
+            let (left, right) = match elt {
+                Element::Wall => (Element::Wall, Element::Wall),
+                Element::Food => (Element::BoxLeft, Element::BoxRight),
+                Element::Robot => (Element::Robot, Element::Empty),
+                Element::Empty => (Element::Empty, Element::Empty),
+                Element::BoxLeft | Element::BoxRight => panic!("the warehouse is already wide"),
+            };
+            let left_pos = Vec2::new(pos.x * 2, pos.y);
+            map[left_pos] = left;
+            map[left_pos + Direction::E] = right;
+        }
+        Warehouse {
+            map,
+            moves: self.moves.clone(),
+            robot: Vec2::new(self.robot.x * 2, self.robot.y),
+        }
+    }
 
-        let (i, j) = self.robot;
-        let move_direction = match to_move {
-            '<' => |u: usize, v: usize| (u, v - 1),
-            '>' => |u: usize, v: usize| (u, v + 1),
-            '^' => |u: usize, v: usize| (u - 1, v),
-            'v' => |u: usize, v: usize| (u + 1, v),
-            cc => panic!("expected one of < v > ^, got {cc}"),
-        };
-        let (mut ni, mut nj) = move_direction(i, j);
-        // println!("{}", self);
-        // dbg!(&self.map[i][j]);
-        // dbg!(&self.map[ni][nj]);
-        match self.map[ni][nj] {
-            Element::Wall => {
-                return;
-            }
-            Element::Robot => panic!("unexpected Element"),
-            Element::Empty => {
-                self.map[i][j] = Element::Empty;
-                self.map[ni][nj] = Element::Robot;
-                self.robot = (ni, nj);
-                return;
-            }
-            Element::Food => (),
-        };
-        // now we have food
-        // what's behind food ? if empty, everything must move, wall, nothing moves, if food again,
-        // loop
-
-        loop {
-            (ni, nj) = move_direction(ni, nj);
-            match self.map[ni][nj] {
-                Element::Wall => {
-                    //if wall nothing moves
-                    return;
-                }
+    // The cells that move when what stands at `pos` steps toward `direction`, along with
+    // everything it pushes, from `pos` outward, or `None` if a wall stops any of them. A wide
+    // box pushed vertically pushes with both halves. Each cell is taken once however many
+    // cells push it, so that a stack of boxes costs its size.
+    fn pushed(&self, pos: Vec2, direction: Direction) -> Option<Vec<Vec2>> {
+        let vertical = matches!(direction, Direction::N | Direction::S);
+        let mut cells = vec![pos];
+        let mut seen = HashSet::from([pos]);
+        let mut i = 0;
+        while let Some(&cell) = cells.get(i) {
+            i += 1;
+            let next = cell + direction;
+            let block = match self.map[next] {
+                Element::Wall => return None,
+                Element::Empty => continue,
                 Element::Robot => panic!("unexpected Element"),
-                Element::Empty => {
-                    self.map[i][j] = Element::Empty;
-                    self.map[ni][nj] = Element::Food;
-                    let (u, v) = move_direction(i, j);
-                    self.map[u][v] = Element::Robot;
-                    self.robot = (u, v);
-                    return;
-                }
-                Element::Food => (),
+                Element::BoxLeft if vertical => [next, next + Direction::E],
+                Element::BoxRight if vertical => [next, next + Direction::W],
+                Element::Food | Element::BoxLeft | Element::BoxRight => [next, next],
             };
+            for cell in block {
+                if seen.insert(cell) {
+                    cells.push(cell);
+                }
+            }
         }
+        Some(cells)
     }
-}
 
-impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let mut pb = Grid::parse_pb(&input);
-        loop {
-            if pb.moves.is_empty() {
-                break;
+    // Plays the next move.
+    fn next_move(&mut self) {
+        let Some(direction) = self.moves.pop() else {
+            return;
+        };
+        // nothing moves unless everything can
+        if let Some(cells) = self.pushed(self.robot, direction) {
+            // the farthest first, into cells already emptied
+            for &pos in cells.iter().rev() {
+                let next = pos + direction;
+                self.map[next] = self.map[pos];
+                self.map[pos] = Element::Empty;
             }
-            pb.next_move();
+            self.robot += direction.offset();
+        }
+    }
+
+    fn run(&mut self) {
+        while !self.moves.is_empty() {
+            self.next_move();
         }
-        let result: usize = pb
-            .map
+    }
+
+    // The sum of the GPS coordinates of the boxes, measured from their left edge.
+    fn gps_sum(&self) -> isize {
+        self.map
             .iter()
-            .enumerate()
-            .map(|(i, line)| {
-                line.iter()
-                    .enumerate()
-                    .filter(|(_, elt)| **elt == Element::Food)
-                    .map(|(j, _)| 100 * i + j)
-                    .sum::<usize>()
+            .filter(|(_, elt)| matches!(elt, Element::Food | Element::BoxLeft))
+            .map(|(pos, _)| 100 * pos.y + pos.x)
+            .sum()
+    }
+}
+
+impl DaySolutions for Problem {
+    type Parsed = Warehouse;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        Warehouse::parse_pb(input)
+    }
+
+    fn part1(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
+        let mut pb = pb.clone();
+        pb.run();
+        Ok(pb.gps_sum().into())
+    }
+
+    fn part2(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
+        let mut pb = pb.widen();
+        pb.run();
+        Ok(pb.gps_sum().into())
+    }
+}
+
+crate::example_tests!(15);
+
+#[cfg(test)]
+mod push_tests {
+    use super::*;
+
+    // A wide warehouse drawn with `[` and `]`, the robot making the `moves`.
+    fn wide(map: &str, moves: &str) -> Warehouse {
+        let map = Grid::parse(DAY, map, |c| {
+            Ok(match c {
+                '[' => Element::BoxLeft,
+                ']' => Element::BoxRight,
+                '#' => Element::Wall,
+                '@' => Element::Robot,
+                _ => Element::Empty,
             })
-            .sum();
-        format!("{}", result)
+        })
+        .unwrap();
+        let robot = map.find(&Element::Robot).unwrap();
+        let moves = moves
+            .chars()
+            .rev()
+            .filter_map(Direction::from_char)
+            .collect();
+        Warehouse { map, moves, robot }
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        // if puzzle == Puzzle::Puzzle {
-        unimplemented!()
-        // }
-        // let input = load_problem(puzzle);
-        // let pb = parse_pb(&input);
-        // let mut result = 0;
-        // format!("{:?}", result)
+    #[test]
+    fn tall_stack_moves_at_once() {
+        let boxes = 60;
+        let mut input = "#####\n#...#\n".to_string();
+        input += &"#.O.#\n".repeat(boxes);
+        input += "#.@.#\n#####\n\n^\n";
+        let mut warehouse = Problem.parse(&input).unwrap().widen();
+        let before = warehouse.gps_sum();
+        warehouse.run();
+        assert_eq!(warehouse.gps_sum(), before - 100 * boxes as isize);
+        assert_eq!(warehouse.robot, Vec2::new(4, boxes as isize + 1));
+    }
+
+    #[test]
+    fn blocked_branch_stops_the_whole_tree() {
+        let blocked = "\
+##########
+##..#...##
+##.[][].##
+##..[]..##
+##..@...##
+##########";
+        let mut warehouse = wide(blocked, "^");
+        let before = warehouse.clone();
+        warehouse.run();
+        assert_eq!(warehouse.map, before.map);
+        assert_eq!(warehouse.robot, before.robot);
+
+        let mut warehouse = wide(&blocked.replacen("##..#...##", "##......##", 1), "^");
+        warehouse.run();
+        assert_eq!(
+            warehouse.map.to_string(),
+            "\
+##########
+##.[][].##
+##..[]..##
+##..@...##
+##......##
+##########"
+        );
     }
 }
@@ -0,0 +1,196 @@
+use crate::{
+    geometry::{Direction, Vec2},
+    AocError,
+};
+
+use super::{Element, Warehouse, DAY};
+
+// What one move changed: the new content of the cells it touched.
+#[derive(Debug, Clone)]
+struct Step {
+    changes: Vec<(Vec2, Element)>,
+    robot: Vec2,
+}
+impl Step {
+    fn apply(&self, warehouse: &mut Warehouse) {
+        warehouse.moves.pop();
+        for &(pos, elt) in self.changes.iter() {
+            warehouse.map[pos] = elt;
+        }
+        warehouse.robot = self.robot;
+    }
+}
+
+/// Every state of a warehouse while its robot moves, to render and check the moves one by
+/// one. Frame 0 is the initial state, frame `i` the state after `i` moves.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    initial: Warehouse,
+    steps: Vec<Step>,
+}
+
+impl Warehouse {
+    /// Plays every move, recording the cells each one changes. They are found by comparing
+    /// the whole map before and after the move, so that `check` does not rely on the moves
+    /// it checks.
+    pub fn replay(&self) -> Replay {
+        let mut warehouse = self.clone();
+        let mut steps = vec![];
+        while !warehouse.moves.is_empty() {
+            let before = warehouse.map.clone();
+            warehouse.next_move();
+            steps.push(Step {
+                changes: warehouse
+                    .map
+                    .iter()
+                    .filter(|&(pos, elt)| before[pos] != *elt)
+                    .map(|(pos, elt)| (pos, *elt))
+                    .collect(),
+                robot: warehouse.robot,
+            });
+        }
+        Replay {
+            initial: self.clone(),
+            steps,
+        }
+    }
+}
+
+impl Replay {
+    /// The number of frames, one more than the number of moves.
+    pub fn len(&self) -> usize {
+        self.steps.len() + 1
+    }
+
+    /// Never, there is always the initial frame.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The warehouse after `index` moves, which displays the move to come.
+    pub fn frame(&self, index: usize) -> Option<Warehouse> {
+        let mut warehouse = self.initial.clone();
+        for step in self.steps.get(..index)? {
+            step.apply(&mut warehouse);
+        }
+        Some(warehouse)
+    }
+
+    /// Every frame, in order.
+    pub fn frames(&self) -> impl Iterator<Item = Warehouse> + '_ {
+        let mut warehouse = self.initial.clone();
+        std::iter::once(warehouse.clone()).chain(self.steps.iter().map(move |step| {
+            step.apply(&mut warehouse);
+            warehouse.clone()
+        }))
+    }
+
+    /// Checks that no move changed a wall, created, destroyed or split a box, and that there
+    /// is always exactly one robot, where the warehouse expects it.
+    pub fn check(&self) -> Result<(), AocError> {
+        let violation = |frame: usize, message: String| {
+            AocError::invalid(DAY, format!("frame {}: {}", frame, message))
+        };
+        // a box is counted once, by its only or its left cell
+        let is_box = |elt: Element| isize::from(matches!(elt, Element::Food | Element::BoxLeft));
+        let is_robot = |elt: Element| isize::from(elt == Element::Robot);
+
+        let mut map = self.initial.map.clone();
+        let boxes: isize = map.iter().map(|(_, elt)| is_box(*elt)).sum();
+        let mut robots: isize = map.iter().map(|(_, elt)| is_robot(*elt)).sum();
+        for (i, step) in self.steps.iter().enumerate() {
+            let frame = i + 1;
+            let mut moved_boxes = 0;
+            for &(pos, elt) in step.changes.iter() {
+                let old = map[pos];
+                if (old == Element::Wall) != (elt == Element::Wall) {
+                    return Err(violation(frame, format!("the wall at {:?} changed", pos)));
+                }
+                moved_boxes += is_box(elt) - is_box(old);
+                robots += is_robot(elt) - is_robot(old);
+                map[pos] = elt;
+            }
+            if moved_boxes != 0 {
+                return Err(violation(
+                    frame,
+                    format!(
+                        "there are {} boxes instead of {}",
+                        boxes + moved_boxes,
+                        boxes
+                    ),
+                ));
+            }
+            for &(pos, _) in step.changes.iter() {
+                let split = match map[pos] {
+                    Element::BoxLeft => map[pos + Direction::E] != Element::BoxRight,
+                    Element::BoxRight => map[pos + Direction::W] != Element::BoxLeft,
+                    _ => false,
+                };
+                if split {
+                    return Err(violation(frame, format!("the box at {:?} was split", pos)));
+                }
+            }
+            if robots != 1 || map[step.robot] != Element::Robot {
+                return Err(violation(
+                    frame,
+                    format!(
+                        "expected a single robot at {:?}, found {}",
+                        step.robot, robots
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DaySolutions, Puzzle};
+
+    #[test]
+    fn example_moves_keep_the_invariants() {
+        let input = Puzzle::Example.load(DAY, 2).unwrap();
+        let warehouse = super::super::Problem.parse(&input).unwrap();
+        for warehouse in [warehouse.clone(), warehouse.widen()] {
+            let replay = warehouse.replay();
+            assert_eq!(replay.len(), warehouse.moves.len() + 1);
+            assert_eq!(replay.check(), Ok(()));
+            let mut played = warehouse.clone();
+            played.run();
+            let last = replay.frame(replay.len() - 1).unwrap();
+            assert_eq!(last.to_string(), played.to_string());
+            assert_eq!(
+                replay.frames().last().unwrap().to_string(),
+                played.to_string()
+            );
+            assert!(replay.frame(replay.len()).is_none());
+            // every frame is the real state, not only the cells the moves report
+            let mut played = warehouse.clone();
+            for frame in replay.frames() {
+                assert_eq!(frame.map, played.map);
+                played.next_move();
+            }
+        }
+    }
+
+    #[test]
+    fn check_catches_a_lost_box() {
+        let input = Puzzle::Example.load(DAY, 1).unwrap();
+        let mut replay = super::super::Problem.parse(&input).unwrap().replay();
+        let step = replay
+            .steps
+            .iter_mut()
+            .find(|step| step.changes.iter().any(|(_, elt)| *elt == Element::Food))
+            .unwrap();
+        let &(pos, _) = step
+            .changes
+            .iter()
+            .find(|(_, elt)| *elt == Element::Food)
+            .unwrap();
+        step.changes.push((pos, Element::Empty));
+        let error = replay.check().unwrap_err().to_string();
+        assert!(error.contains("boxes instead of"), "{}", error);
+    }
+}
@@ -1,42 +1,29 @@
-use std::fs;
-
 use itertools::Itertools;
 
-use crate::{DaySolutions, Puzzle};
+use crate::{parse::words, Answer, AocError, DaySolutions, Progress};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        Puzzle::Example => fs::read_to_string("src/day2/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day2/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = 2;
 
-fn read_lists(pb: String) -> Vec<Vec<i32>> {
+fn read_lists(pb: &str) -> Result<Vec<Vec<i32>>, AocError> {
     let mut line_lists: Vec<Vec<i32>> = vec![];
     for line in pb.lines() {
-        let sub_list: Vec<i32> = line
-            .split_whitespace()
-            .map(|v| v.parse::<i32>().unwrap())
-            .collect();
+        let sub_list: Vec<i32> = words(DAY, pb, line)?;
         line_lists.push(sub_list);
     }
-    line_lists
+    Ok(line_lists)
 }
 
-fn is_safe(line: &Vec<i32>) -> bool {
+fn is_safe(line: &[i32]) -> bool {
     let deltas = line
         .windows(2)
         .map(|chunck| chunck[1] - chunck[0])
         .collect_vec();
     for i in 0..deltas.len() {
-        if i != 0 {
-            if deltas[i] * deltas[i - 1] < 0 {
-                return false;
-            }
+        if i != 0 && deltas[i] * deltas[i - 1] < 0 {
+            return false;
         }
         if (deltas[i]).abs() > 3 || deltas[i] == 0 {
             return false;
@@ -45,7 +32,7 @@ fn is_safe(line: &Vec<i32>) -> bool {
     true
 }
 
-fn is_almost_safe(line: &Vec<i32>) -> bool {
+fn is_almost_safe(line: &[i32]) -> bool {
     if is_safe(line) {
         return true;
     }
@@ -64,27 +51,31 @@ fn is_almost_safe(line: &Vec<i32>) -> bool {
 }
 
 impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        let pb = load_problem(puzzle);
-        let line_lists = read_lists(pb);
+    type Parsed = Vec<Vec<i32>>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        read_lists(input)
+    }
+
+    fn part1(&self, line_lists: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
         let mut result = 0;
         for line in line_lists.iter() {
-            if is_safe(&line) {
+            if is_safe(line) {
                 result += 1;
             }
         }
-        format!("{:?}", result)
+        Ok(result.into())
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        let pb = load_problem(puzzle);
-        let line_lists = read_lists(pb);
+    fn part2(&self, line_lists: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
         let mut result = 0;
         for line in line_lists.iter() {
-            if is_almost_safe(&line) {
+            if is_almost_safe(line) {
                 result += 1;
             }
         }
-        format!("{:?}", result)
+        Ok(result.into())
     }
 }
+
+crate::example_tests!(2);
@@ -1,48 +1,49 @@
-use std::{collections::{HashMap, VecDeque}, fs};
+use std::collections::HashMap;
 
-use crate::{DaySolutions, Puzzle};
+use crate::{
+    parse::{n_separated, separated, two_sections},
+    toposort::topological_sort,
+    Answer, AocError, DaySolutions, Progress,
+};
 
 #[derive(Clone)]
 pub struct Problem;
 
+const DAY: usize = 5;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        Puzzle::Example => fs::read_to_string("src/day5/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day5/puzzle.txt").unwrap(),
-    };
-    content
-}
+type Rules = Vec<(i32, i32)>;
+type Updates = Vec<Vec<i32>>;
 
-fn parse_problem(input: &str) -> (Vec<(i32, i32)>, Vec<Vec<i32>>) {
-    let mut sections = input.split("\n\n");
-    let rules_section = sections.next().unwrap();
-    let updates_section = sections.next().unwrap();
+fn parse_problem(input: &str) -> Result<(Rules, Updates), AocError> {
+    let (rules_section, updates_section) =
+        two_sections(DAY, input, "the ordering rules and the updates")?;
 
     let rules = rules_section
         .lines()
         .map(|line| {
-            let parts: Vec<_> = line.split('|').map(|x| x.parse::<i32>().unwrap()).collect();
-            (parts[0], parts[1])
+            let [before, after] = n_separated(DAY, input, line, '|', "a rule like `47|53`")?;
+            Ok((before, after))
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
     let updates = updates_section
         .lines()
-        .map(|line| {
-            line.split(',')
-                .map(|x| x.parse::<i32>().unwrap())
-                .collect::<Vec<i32>>()
-        })
-        .collect();
+        .map(|line| separated(DAY, input, line, ','))
+        .collect::<Result<_, _>>()?;
 
-    (rules, updates)
+    Ok((rules, updates))
 }
 
 fn is_update_valid(update: &[i32], rules: &[(i32, i32)]) -> bool {
-    let position_map: HashMap<i32, usize> = update.iter().enumerate().map(|(i, &page)| (page, i)).collect();
+    let position_map: HashMap<i32, usize> = update
+        .iter()
+        .enumerate()
+        .map(|(i, &page)| (page, i))
+        .collect();
     for &(before, after) in rules {
-        if let (Some(&pos_before), Some(&pos_after)) = (position_map.get(&before), position_map.get(&after)) {
+        if let (Some(&pos_before), Some(&pos_after)) =
+            (position_map.get(&before), position_map.get(&after))
+        {
             if pos_before > pos_after {
                 return false;
             }
@@ -51,56 +52,33 @@ fn is_update_valid(update: &[i32], rules: &[(i32, i32)]) -> bool {
     true
 }
 
-fn reorder_update(update: &[i32], rules: &[(i32, i32)]) -> Vec<i32> {
-    // create a graph representation
-    let mut graph: HashMap<i32, Vec<i32>> = HashMap::new();
-    let mut in_degree: HashMap<i32, usize> = HashMap::new();
-
-    for &page in update {
-        graph.entry(page).or_default();
-        in_degree.entry(page).or_insert(0);
-    }
-
-    // add edges
-    for &(before, after) in rules {
-        if update.contains(&before) && update.contains(&after) {
-            graph.get_mut(&before).unwrap().push(after);
-            *in_degree.entry(after).or_default() += 1;
-        }
-    }
-
-    // topological sort
-    let mut sorted: Vec<i32> = Vec::new();
-    let mut queue: VecDeque<i32> = in_degree
-        .iter()
-        .filter(|&(_, &deg)| deg == 0)
-        .map(|(&page, _)| page)
-        .collect();
-
-    while let Some(page) = queue.pop_front() {
-        sorted.push(page);
-        if let Some(neighbors) = graph.get(&page) {
-            for &neighbor in neighbors {
-                let degree = in_degree.get_mut(&neighbor).unwrap();
-                *degree -= 1;
-                if *degree == 0 {
-                    queue.push_back(neighbor);
-                }
-            }
-        }
-    }
-
-    sorted
+fn reorder_update(update: &[i32], rules: &[(i32, i32)]) -> Result<Vec<i32>, AocError> {
+    topological_sort(update, rules.iter().copied()).map_err(|cycle| {
+        AocError::invalid(
+            DAY,
+            format!(
+                "the rules cannot order the update, they form a cycle: {}",
+                cycle
+            ),
+        )
+    })
 }
 
 impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let (rules, updates) = parse_problem(&input);
+    type Parsed = (Rules, Updates);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_problem(input)
+    }
 
+    fn part1(
+        &self,
+        (rules, updates): &Self::Parsed,
+        _progress: &Progress,
+    ) -> Result<Answer, AocError> {
         let valid_updates: Vec<Vec<i32>> = updates
             .iter()
-            .filter(|update| is_update_valid(update, &rules))
+            .filter(|update| is_update_valid(update, rules))
             .cloned()
             .collect();
 
@@ -109,25 +87,54 @@ impl DaySolutions for Problem {
             .map(|update| update[update.len() / 2])
             .sum();
 
-        format!("{}", middle_sum)
+        Ok(middle_sum.into())
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let (rules, updates) = parse_problem(&input);
-
+    fn part2(
+        &self,
+        (rules, updates): &Self::Parsed,
+        _progress: &Progress,
+    ) -> Result<Answer, AocError> {
         let invalid_updates: Vec<Vec<i32>> = updates
             .iter()
-            .filter(|update| !is_update_valid(update, &rules))
-            .map(|update| reorder_update(update, &rules))
-            .collect();
+            .filter(|update| !is_update_valid(update, rules))
+            .map(|update| reorder_update(update, rules))
+            .collect::<Result<_, _>>()?;
 
         let middle_sum: i32 = invalid_updates
             .iter()
             .map(|update| update[update.len() / 2]) // Get the middle element
             .sum();
 
-        format!("{}", middle_sum)
+        Ok(middle_sum.into())
     }
 }
 
+crate::example_tests!(5);
+
+#[cfg(test)]
+mod error_tests {
+    use super::*;
+
+    #[test]
+    fn part2_reports_cyclic_rules() {
+        let input = "1|2\n2|3\n3|1\n\n3,2,1\n";
+        let parsed = Problem.parse(input).unwrap();
+        let error = Problem.part2(&parsed, &Progress::hidden()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day 5: the rules cannot order the update, they form a cycle: 3 -> 1 -> 2 -> 3"
+        );
+    }
+
+    #[test]
+    fn malformed_rules_and_updates_are_rejected() {
+        for input in ["47-53\n\n47,53\n", "47|53\n\nabc\n", "47|53\n\n47,53\n \n"] {
+            assert!(
+                matches!(Problem.parse(input), Err(AocError::Parse { .. })),
+                "{:?}",
+                input
+            );
+        }
+    }
+}
@@ -1,62 +1,40 @@
-use core::panic;
-use std::{collections::HashSet, fmt::Display, fs, ops};
+use std::fmt::Display;
 
-use itertools::Itertools;
+use rayon::prelude::*;
 
-use crate::{DaySolutions, Puzzle};
+use crate::{
+    geometry::{Direction, Vec2},
+    grid::Grid,
+    Answer, AocError, DaySolutions, Progress,
+};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        Puzzle::Example => fs::read_to_string("src/day6/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day6/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = 6;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    N,
-    S,
-    E,
-    W,
-}
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Cell {
-    Guard(Direction),
     Empty,
-    Visited,
     Obstacle,
 }
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Pos {
-    x: isize,
-    y: isize,
-}
-impl ops::Add<Pos> for Pos {
-    type Output = Pos;
-    fn add(self, rhs: Pos) -> Self::Output {
-        Pos {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-impl Pos {
-    fn new(x: isize, y: isize) -> Self {
-        Self { x, y }
+impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Cell::Empty => '.',
+            Cell::Obstacle => '#',
+        };
+        write!(f, "{}", c)
     }
 }
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
-struct Guard {
-    position: Pos,
-    direction: Direction,
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Guard {
+    pub position: Vec2,
+    pub direction: Direction,
 }
 impl Guard {
-    fn new(position: Pos, direction: Direction) -> Self {
+    fn new(position: Vec2, direction: Direction) -> Self {
         Self {
             position,
             direction,
@@ -64,185 +42,294 @@ impl Guard {
     }
 }
 
+// The guard states already seen, one bit per cell and direction.
+struct Visited {
+    bits: Vec<u64>,
+    width: usize,
+}
+impl Visited {
+    fn new(map: &Grid<Cell>) -> Self {
+        Visited {
+            bits: vec![0; (map.width() * map.height() * 4).div_ceil(64)],
+            width: map.width(),
+        }
+    }
+    fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+    /// Marks `guard`, returning whether it was not marked yet.
+    fn insert(&mut self, guard: Guard) -> bool {
+        let cell = guard.position.y as usize * self.width + guard.position.x as usize;
+        // the guard only faces cardinal directions, the even ones
+        let bit = cell * 4 + guard.direction as usize / 2;
+        let (word, mask) = (bit / 64, 1 << (bit % 64));
+        let new = self.bits[word] & mask == 0;
+        self.bits[word] |= mask;
+        new
+    }
+}
+
+/// An obstacle that traps the guard, with the states the guard then goes through forever.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loop {
+    pub obstacle: Vec2,
+    pub cycle: Vec<Guard>,
+}
+
 #[derive(Debug, Clone)]
-struct Map {
-    map: Vec<Vec<Cell>>,
-    max_x: isize,
-    max_y: isize,
+pub struct Map {
+    map: Grid<Cell>,
     guard: Guard,
-    visited: HashSet<Guard>,
-    counter: i32,
 }
-impl Display for Map {
+/// The map with the walk of the guard drawn over it as in the puzzle, `|` and `-` where it
+/// goes straight and `+` where it turns or crosses its path, and an extra obstacle as `O`.
+pub struct Overlay<'a> {
+    map: &'a Map,
+    obstacle: Option<Vec2>,
+}
+impl Display for Overlay<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let out = self
-            .map
-            .iter()
-            .map(|line| {
-                line.iter()
-                    .map(|v| match v {
-                        Cell::Guard(direction) => match direction {
-                            Direction::N => "^",
-                            Direction::S => "v",
-                            Direction::E => ">",
-                            Direction::W => "<",
-                        },
-                        Cell::Empty => ".",
-                        Cell::Visited => "X",
-                        Cell::Obstacle => "#",
-                    })
-                    .join("")
-            })
-            .join("\n");
-        write!(f, "{}", &out)
+        let map = self.map;
+        // the axes the guard walks along on each cell
+        let mut axes = Grid::new(map.map.width(), map.map.height(), (false, false));
+        let (walk, _) = map.trace(map.guard, self.obstacle);
+        for guard in walk {
+            let (vertical, horizontal) = &mut axes[guard.position];
+            match guard.direction {
+                Direction::N | Direction::S => *vertical = true,
+                _ => *horizontal = true,
+            }
+        }
+        for (i, row) in map.map.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, cell) in row.iter().enumerate() {
+                let position = Vec2::new(j as isize, i as isize);
+                if position == map.guard.position {
+                    write!(f, "{}", map.guard.direction.to_char().unwrap_or('?'))?;
+                } else if Some(position) == self.obstacle {
+                    write!(f, "O")?;
+                } else {
+                    match axes[position] {
+                        (true, true) => write!(f, "+")?,
+                        (true, false) => write!(f, "|")?,
+                        (false, true) => write!(f, "-")?,
+                        (false, false) => write!(f, "{}", cell)?,
+                    }
+                }
+            }
+        }
+        Ok(())
     }
 }
-impl Map {
-    fn parse_problem(input: &str) -> Self {
-        let mut map = vec![];
-        let mut guard_position = Pos { x: 0, y: 0 };
-        let mut guard_direction = None;
-        for (x, line) in input.lines().enumerate() {
-            map.push(
-                line.chars()
-                    .enumerate()
-                    .map(|(y, c)| match c {
-                        '.' => Cell::Empty,
-                        '#' => Cell::Obstacle,
-                        'X' => Cell::Visited,
-                        _ => {
-                            guard_position = Pos {
-                                x: x.try_into().unwrap(),
-                                y: y.try_into().unwrap(),
-                            };
-                            if c == '^' {
-                                guard_direction = Some(Direction::N);
-                            } else if c == 'v' {
-                                guard_direction = Some(Direction::S);
-                            } else if c == '>' {
-                                guard_direction = Some(Direction::E);
-                            } else if c == '<' {
-                                guard_direction = Some(Direction::W);
-                            } else {
-                                panic!("wrong charcter parsed: {:?}", c)
-                            }
-                            Cell::Guard(guard_direction.unwrap())
-                        }
-                    })
-                    .collect_vec(),
-            )
+
+// `{:#}` draws the patrol path too.
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.overlay(None));
         }
-        let max_x = (map.len() - 1).try_into().unwrap();
-        let max_y = (map.last().unwrap().len() - 1).try_into().unwrap();
-        Map {
-            map,
-            max_x,
-            max_y,
-            visited: HashSet::new(),
-            counter: 0,
-            guard: Guard::new(guard_position, guard_direction.unwrap()),
+        for (i, row) in self.map.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, cell) in row.iter().enumerate() {
+                if Vec2::new(j as isize, i as isize) == self.guard.position {
+                    write!(f, "{}", self.guard.direction.to_char().unwrap_or('?'))?;
+                } else {
+                    write!(f, "{}", cell)?;
+                }
+            }
         }
+        Ok(())
     }
-    fn get(&self, pos: Pos) -> Cell {
-        self.map[pos.x as usize][pos.y as usize]
+}
+impl Map {
+    fn parse_problem(input: &str) -> Result<Self, AocError> {
+        let mut guard = None;
+        let mut cells = 0;
+        let map = Grid::parse(DAY, input, |c| {
+            let position = cells;
+            cells += 1;
+            Ok(match c {
+                '.' | 'X' => Cell::Empty,
+                '#' => Cell::Obstacle,
+                '^' | 'v' | '>' | '<' => {
+                    if guard.is_some() {
+                        return Err("there is more than one guard".to_string());
+                    }
+                    guard = Some((position, Direction::from_char(c).unwrap()));
+                    Cell::Empty
+                }
+                _ => return Err(format!("wrong character parsed: {:?}", c)),
+            })
+        })?;
+        let Some((cell, direction)) = guard else {
+            return Err(AocError::invalid(DAY, "there is no guard on the map"));
+        };
+        let position = Vec2::new((cell % map.width()) as isize, (cell / map.width()) as isize);
+        Ok(Map {
+            map,
+            guard: Guard::new(position, direction),
+        })
     }
-    fn get_guard_direction(&self) -> Direction {
-        match self.get(self.guard.position) {
-            Cell::Guard(dir) => dir,
-            _ => panic!("there is no guard at this position"),
+
+    // Where the guard is after its next move, with an extra `obstacle` on the map. `None`
+    // once the guard leaves the map.
+    fn next(&self, guard: Guard, obstacle: Option<Vec2>) -> Option<Guard> {
+        let ahead = guard.position + guard.direction;
+        match self.map.get(ahead)? {
+            Cell::Obstacle => Some(Guard::new(guard.position, guard.direction.turn_right())),
+            Cell::Empty if Some(ahead) == obstacle => {
+                Some(Guard::new(guard.position, guard.direction.turn_right()))
+            }
+            Cell::Empty => Some(Guard::new(ahead, guard.direction)),
         }
     }
-    fn map_edit(&mut self, pos: Pos, new_value: Cell) {
-        self.map[pos.x as usize][pos.y as usize] = new_value;
-    }
-    fn step(&mut self) -> Option<bool> {
-        let direction = self.get_guard_direction();
-
-        let next_position = match direction {
-            Direction::N => self.guard.position + Pos::new(-1, 0),
-            Direction::S => self.guard.position + Pos::new(1, 0),
-            Direction::E => self.guard.position + Pos::new(0, 1),
-            Direction::W => self.guard.position + Pos::new(0, -1),
-        };
 
-        if next_position.x < 0
-            || next_position.x > self.max_x
-            || next_position.y < 0
-            || next_position.y > self.max_y
-        {
-            self.counter += 1;
-            return Some(false);
-        }
-        match self.get(next_position) {
-            Cell::Empty => {
-                self.map_edit(next_position, Cell::Guard(direction));
-                self.map_edit(self.guard.position, Cell::Visited);
-                self.guard.position = next_position;
-                self.counter += 1;
-                self.visited.insert(self.guard.clone());
-                Some(true)
+    // Every cell the guard patrols but its starting one, in the order it first enters them,
+    // with the guard just before it does.
+    fn patrol(&self) -> Result<Vec<(Vec2, Guard)>, AocError> {
+        let mut entered = Grid::new(self.map.width(), self.map.height(), false);
+        entered[self.guard.position] = true;
+        let mut visited = Visited::new(&self.map);
+        let mut path = vec![];
+        let mut guard = self.guard;
+        while let Some(next) = self.next(guard, None) {
+            if !visited.insert(next) {
+                return Err(AocError::invalid(DAY, "the guard never leaves the map"));
             }
-            Cell::Visited => {
-                self.map_edit(next_position, Cell::Guard(direction));
-                self.map_edit(self.guard.position, Cell::Visited);
-                self.guard.position = next_position;
-                if self.visited.contains(&self.guard) {
-                    return None;
-                }
-                self.visited.insert(self.guard.clone());
-                Some(true)
+            if !entered[next.position] {
+                entered[next.position] = true;
+                path.push((next.position, guard));
             }
-            Cell::Obstacle => {
-                self.guard.direction = match direction {
-                    Direction::N => Direction::E,
-                    Direction::E => Direction::S,
-                    Direction::S => Direction::W,
-                    Direction::W => Direction::N,
-                };
-                self.map_edit(self.guard.position, Cell::Guard(self.guard.direction));
-                Some(true)
+            guard = next;
+        }
+        Ok(path)
+    }
+
+    // The guard states from `guard` on, with an extra `obstacle`, until the guard leaves the
+    // map or comes back to a state already seen, and whether it came back.
+    fn trace(&self, mut guard: Guard, obstacle: Option<Vec2>) -> (Vec<Guard>, bool) {
+        let mut visited = Visited::new(&self.map);
+        visited.insert(guard);
+        let mut walk = vec![guard];
+        while let Some(next) = self.next(guard, obstacle) {
+            if !visited.insert(next) {
+                return (walk, true);
             }
-            a => panic!("{:?}", a),
+            walk.push(next);
+            guard = next;
         }
+        (walk, false)
     }
-    fn play(&mut self) -> i32 {
-        loop {
-            match self.step() {
-                Some(true) => (),
-                Some(false) => return self.counter,
-                None => return -1,
-            };
+
+    // The first state the guard, from `guard`, comes back to once `obstacle` is added, if it
+    // walks in a loop.
+    fn loops_with(&self, mut guard: Guard, obstacle: Vec2, visited: &mut Visited) -> Option<Guard> {
+        visited.clear();
+        while let Some(next) = self.next(guard, Some(obstacle)) {
+            if !visited.insert(next) {
+                return Some(next);
+            }
+            guard = next;
+        }
+        None
+    }
+
+    // The states of the loop through `start` once `obstacle` is added, from `start` until
+    // the guard comes back to it. No visited set is needed, `start` is known to be on it.
+    fn cycle(&self, start: Guard, obstacle: Vec2) -> Vec<Guard> {
+        std::iter::successors(Some(start), |&guard| {
+            self.next(guard, Some(obstacle))
+                .filter(|&next| next != start)
+        })
+        .collect()
+    }
+
+    /// Every obstacle that, added alone, makes the guard walk in a loop, in the order the
+    /// patrol reaches them.
+    pub fn loops(&self, progress: &Progress) -> Result<Vec<Loop>, AocError> {
+        // An obstacle off the patrol path changes nothing. On the path, the guard walks the
+        // same way until it first bumps into it, so start from there.
+        let candidates = self.patrol()?;
+        let tried = progress.bar("day 6: obstacles", candidates.len() as u64);
+        Ok(candidates
+            .par_iter()
+            .map_init(
+                || Visited::new(&self.map),
+                |visited, &(obstacle, before)| {
+                    tried.inc(1);
+                    let back = self.loops_with(before, obstacle, visited)?;
+                    Some(Loop {
+                        obstacle,
+                        cycle: self.cycle(back, obstacle),
+                    })
+                },
+            )
+            .flatten()
+            .collect())
+    }
+
+    /// Draws the walk of the guard, with an extra `obstacle`.
+    pub fn overlay(&self, obstacle: Option<Vec2>) -> Overlay<'_> {
+        Overlay {
+            map: self,
+            obstacle,
         }
     }
 }
 impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let mut pb = Map::parse_problem(&input);
-        format!("\n{}", pb.play())
-    }
-
-    fn part2(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let mut result = 0;
-        let pb = Map::parse_problem(&input);
-        // Could highly be improved by adding the obsutruction only on the path of the guard...
-        // Any way it happened to work on my tiny computer in not so much time
-        for i in 0..(pb.max_x + 1) {
-            for j in 0..(pb.max_y + 1) {
-                match pb.get(Pos::new(i, j)) {
-                    Cell::Empty => {
-                        let mut n_pb = pb.clone();
-                        n_pb.map_edit(Pos::new(i, j), Cell::Obstacle);
-                        let play = n_pb.play();
-                        if play < 0 {
-                            result += 1;
-                        }
-                    }
-                    _ => (),
-                }
-            }
-        }
-        format!("{}", result)
+    type Parsed = Map;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        Map::parse_problem(input)
+    }
+
+    fn part1(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
+        // the starting cell is patrolled too
+        Ok((pb.patrol()?.len() + 1).into())
+    }
+
+    fn part2(&self, pb: &Self::Parsed, progress: &Progress) -> Result<Answer, AocError> {
+        Ok(pb.loops(progress)?.len().into())
+    }
+}
+
+crate::example_tests!(6);
+
+#[cfg(test)]
+mod loop_tests {
+    use super::*;
+    use crate::Puzzle;
+
+    #[test]
+    fn example_loops_are_drawn() {
+        let input = Puzzle::Example.load(DAY, 2).unwrap();
+        let map = Problem.parse(&input).unwrap();
+        let loops = map.loops(&Progress::hidden()).unwrap();
+        let mut obstacles: Vec<_> = loops.iter().map(|l| (l.obstacle.x, l.obstacle.y)).collect();
+        obstacles.sort();
+        assert_eq!(obstacles, [(1, 8), (3, 6), (3, 8), (6, 7), (7, 7), (7, 9)]);
+
+        let first = loops
+            .iter()
+            .find(|l| l.obstacle == Vec2::new(3, 6))
+            .unwrap();
+        // 18 steps around the rectangle and a turn at each corner
+        assert_eq!(first.cycle.len(), 18 + 4);
+        let drawn = "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.O^---+.
+........#.
+#.........
+......#...";
+        assert_eq!(map.overlay(Some(first.obstacle)).to_string(), drawn);
     }
 }
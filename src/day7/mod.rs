@@ -1,21 +1,15 @@
 use core::panic;
-use std::fs;
 
 use itertools::Itertools;
 use radix_fmt::radix;
 
-use crate::{DaySolutions, Puzzle};
+use crate::{parse::key_values, Answer, AocError, DaySolutions, Progress};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        Puzzle::Example => fs::read_to_string("src/day7/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day7/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = 7;
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 enum Op {
     #[default]
@@ -24,20 +18,18 @@ enum Op {
     Concat,
 }
 #[derive(Debug, Clone)]
-struct Equation {
+pub struct Equation {
     result: i64,
     terms: Vec<i64>,
     operators: Vec<Op>,
 }
-fn parse_pb(input: &str) -> Vec<Equation> {
+fn parse_pb(input: &str) -> Result<Vec<Equation>, AocError> {
     let mut pb: Vec<Equation> = vec![];
     for line in input.lines() {
-        let (result, terms) = line.split_once(": ").unwrap();
-        let result = result.parse().unwrap();
-        let terms = terms
-            .split(" ")
-            .map(|elt| elt.parse().unwrap())
-            .collect_vec();
+        let (result, terms) = key_values(DAY, input, line)?;
+        if terms.is_empty() {
+            return Err(AocError::at(DAY, input, line, "expected at least one term"));
+        }
         let operators = (0..(terms.len() - 1)).map(|_| Op::default()).collect_vec();
         pb.push(Equation {
             result,
@@ -45,7 +37,7 @@ fn parse_pb(input: &str) -> Vec<Equation> {
             operators,
         });
     }
-    pb
+    Ok(pb)
 }
 
 impl Equation {
@@ -157,31 +149,33 @@ impl Equation {
 }
 
 impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let pb = parse_pb(&input);
+    type Parsed = Vec<Equation>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_pb(input)
+    }
+
+    fn part1(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
         let mut result = 0;
         for eq in pb {
-            match eq.clone().check_equation() {
-                Some(_) => result += eq.result,
-                None => (),
+            if eq.clone().check_equation().is_some() {
+                result += eq.result
             }
         }
 
-        format!("{:?}", result)
+        Ok(result.into())
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let pb = parse_pb(&input);
+    fn part2(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
         let mut result = 0;
         for eq in pb {
-            match eq.clone().check_equation2() {
-                Some(_) => result += eq.result,
-                None => (),
+            if eq.clone().check_equation2().is_some() {
+                result += eq.result
             }
         }
 
-        format!("{:?}", result)
+        Ok(result.into())
     }
 }
+
+crate::example_tests!(7);
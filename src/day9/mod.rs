@@ -1,29 +1,28 @@
-use std::fs;
+use std::cmp::Reverse;
 
-use itertools::Itertools;
-
-use crate::{DaySolutions, Puzzle};
+use crate::{Answer, AocError, DaySolutions, Progress};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        //TODO: change the directory name
-        Puzzle::Example => fs::read_to_string("src/day9/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/day9/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = 9;
 
-fn parse_pb(input: &str) -> Vec<usize> {
+fn parse_pb(input: &str) -> Result<Vec<usize>, AocError> {
     input
-        .chars()
-        .filter(|c| *c != '\n')
-        .map(|c| c.to_digit(10).unwrap() as usize)
-        .collect_vec()
+        .char_indices()
+        .filter(|(_, c)| !c.is_ascii_whitespace())
+        .map(|(i, c)| match c.to_digit(10) {
+            Some(size) => Ok(size as usize),
+            None => Err(AocError::at(
+                DAY,
+                input,
+                &input[i..],
+                format!("expected a digit, found {:?}", c),
+            )),
+        })
+        .collect()
 }
-fn expand(file: Vec<usize>) -> Vec<Option<usize>> {
+fn expand(file: &[usize]) -> Vec<Option<usize>> {
     let mut expanded = vec![];
     for (i, size) in file.iter().enumerate() {
         let data = if i % 2 == 1 { None } else { Some(i / 2) };
@@ -33,21 +32,18 @@ fn expand(file: Vec<usize>) -> Vec<Option<usize>> {
     }
     expanded
 }
-fn swap(expanded: &mut Vec<Option<usize>>) -> Vec<Option<usize>> {
-    let mut i;
-    let mut j;
+fn swap(expanded: &mut [Option<usize>]) -> Vec<Option<usize>> {
+    // i walks forward to the next free block, j backward to the last file block
+    let mut i = 0;
+    let mut j = expanded.len().saturating_sub(1);
     loop {
-        j = expanded.len()
-            - 1
-            - expanded
-                .iter()
-                .rev()
-                .find_position(|e| e.is_some())
-                .unwrap()
-                .0;
-
-        i = expanded.iter().find_position(|e| e.is_none()).unwrap().0;
-        if i > j {
+        while i < j && expanded[i].is_some() {
+            i += 1;
+        }
+        while i < j && expanded[j].is_none() {
+            j -= 1;
+        }
+        if i >= j {
             break;
         }
         expanded.swap(i, j);
@@ -55,7 +51,7 @@ fn swap(expanded: &mut Vec<Option<usize>>) -> Vec<Option<usize>> {
     expanded.to_vec()
 }
 
-fn swap2(expanded: &mut Vec<Option<usize>>) -> Vec<Option<usize>> {
+fn swap2(expanded: &mut [Option<usize>]) -> Vec<Option<usize>> {
     let mut file_positions: Vec<(usize, std::ops::Range<usize>)> = vec![];
     let mut current_id = None;
     let mut start = 0;
@@ -73,7 +69,7 @@ fn swap2(expanded: &mut Vec<Option<usize>>) -> Vec<Option<usize>> {
         file_positions.push((id, start..expanded.len()));
     }
 
-    file_positions.sort_by(|a, b| b.0.cmp(&a.0));
+    file_positions.sort_by_key(|(id, _)| Reverse(*id));
 
     for (file_id, range) in file_positions {
         let file_size = range.len();
@@ -94,12 +90,13 @@ fn swap2(expanded: &mut Vec<Option<usize>>) -> Vec<Option<usize>> {
 }
 
 impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        if puzzle == Puzzle::Puzzle {
-            return "".to_string();
-        }
-        let input = load_problem(puzzle);
-        let pb = parse_pb(&input);
+    type Parsed = Vec<usize>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_pb(input)
+    }
+
+    fn part1(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
         let mut expanded = expand(pb);
         let result: usize = swap(&mut expanded)
             .iter()
@@ -107,12 +104,10 @@ impl DaySolutions for Problem {
             .filter(|(_, v)| v.is_some())
             .map(|(i, v)| i * v.unwrap())
             .sum();
-        format!("{:?}", result)
+        Ok(result.into())
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        let input = load_problem(puzzle);
-        let pb = parse_pb(&input);
+    fn part2(&self, pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
         let mut expanded = expand(pb);
         let result: usize = swap2(&mut expanded)
             .iter()
@@ -120,6 +115,8 @@ impl DaySolutions for Problem {
             .filter(|(_, v)| v.is_some())
             .map(|(i, v)| i * v.unwrap())
             .sum();
-        format!("{:?}", result)
+        Ok(result.into())
     }
 }
+
+crate::example_tests!(9);
@@ -0,0 +1,110 @@
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AocError {
+    /// The input does not match the expected format. `line` and `column` are 1-based.
+    Parse {
+        day: usize,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input is well formed but cannot be solved.
+    Invalid {
+        day: usize,
+        message: String,
+    },
+    /// The input could not be read.
+    Io {
+        day: usize,
+        message: String,
+    },
+    NotImplemented {
+        day: usize,
+        part: u8,
+    },
+    /// A solver was called with arguments it does not accept, e.g. a part other than 1 or 2.
+    Misuse {
+        message: String,
+    },
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::Parse {
+                day,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "day {}, line {}, column {}: {}",
+                day, line, column, message
+            ),
+            AocError::Invalid { day, message } => write!(f, "day {}: {}", day, message),
+            AocError::Io { day, message } => {
+                write!(f, "day {}: cannot read input: {}", day, message)
+            }
+            AocError::NotImplemented { day, part } => {
+                write!(f, "day {} part {} is not implemented", day, part)
+            }
+            AocError::Misuse { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AocError {}
+
+impl AocError {
+    /// Builds a parse error pointing at `fragment`, which must be a slice of `input`.
+    pub fn at(day: usize, input: &str, fragment: &str, message: impl Into<String>) -> Self {
+        let (start, at) = (input.as_ptr() as usize, fragment.as_ptr() as usize);
+        debug_assert!(
+            start <= at && at + fragment.len() <= start + input.len(),
+            "{:?} is not a slice of the input",
+            fragment
+        );
+        let mut offset = (fragment.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rfind('\n')
+            .map_or(before.chars().count(), |i| before[i + 1..].chars().count())
+            + 1;
+        AocError::Parse {
+            day,
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid(day: usize, message: impl Into<String>) -> Self {
+        AocError::Invalid {
+            day,
+            message: message.into(),
+        }
+    }
+}
+
+/// Parses `token`, a slice of `input`, reporting where it is on failure.
+pub fn parse_token<T>(day: usize, input: &str, token: &str) -> Result<T, AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    token.parse().map_err(|e| {
+        AocError::at(
+            day,
+            input,
+            token,
+            format!("cannot parse {:?}: {}", token, e),
+        )
+    })
+}
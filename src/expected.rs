@@ -0,0 +1,128 @@
+use std::fs;
+
+use crate::{Answer, AocError, Puzzle};
+
+/// Known answers of a day, read from `src/dayN/answers.toml`:
+///
+/// ```toml
+/// [example]
+/// part1 = 11
+/// part2 = "some text"
+///
+/// [puzzle]
+/// part1 = [7892, 8011]              # several candidates
+/// part2 = { not_applicable = true }
+/// ```
+///
+/// A missing section or key means the answer is not known yet.
+#[derive(Debug, Default)]
+pub struct Expected {
+    table: toml::Table,
+}
+
+impl Expected {
+    /// Reads the answers of `day`. A day without an answers file has no known answers.
+    pub fn load(day: usize) -> Result<Self, AocError> {
+        let path = Puzzle::day_path(day).join("answers.toml");
+        if !path.exists() {
+            return Ok(Expected::default());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| AocError::Io {
+            day,
+            message: e.to_string(),
+        })?;
+        let table = content
+            .parse()
+            .map_err(|e| AocError::invalid(day, format!("{}: {}", path.display(), e)))?;
+        Ok(Expected { table })
+    }
+
+    /// The expected answer of `part` on `puzzle`, if known. Only the bundled inputs have
+    /// expected answers.
+    pub fn get(&self, day: usize, puzzle: &Puzzle, part: u8) -> Result<Option<Answer>, AocError> {
+        let section = match puzzle {
+            Puzzle::Example => "example",
+            Puzzle::Puzzle => "puzzle",
+            Puzzle::File(_) | Puzzle::Stdin => return Ok(None),
+        };
+        self.table
+            .get(section)
+            .and_then(|answers| answers.get(format!("part{}", part)))
+            .map(|value| to_answer(day, value))
+            .transpose()
+    }
+}
+
+fn to_answer(day: usize, value: &toml::Value) -> Result<Answer, AocError> {
+    match value {
+        toml::Value::Integer(n) => Ok(Answer::Int(*n as i128)),
+        toml::Value::String(s) => Ok(Answer::Str(s.clone())),
+        toml::Value::Array(values) => values
+            .iter()
+            .map(|value| to_answer(day, value))
+            .collect::<Result<_, _>>()
+            .map(Answer::Multi),
+        toml::Value::Table(table)
+            if table.get("not_applicable") == Some(&toml::Value::Boolean(true)) =>
+        {
+            Ok(Answer::NotApplicable)
+        }
+        other => Err(AocError::invalid(
+            day,
+            format!("unsupported expected answer {}", other),
+        )),
+    }
+}
+
+/// Runs `part` of `solutions` on the day's example and compares it with the `[example]`
+/// answer. A part without a known answer must report itself as not implemented, both when
+/// run and in `DaySolutions::PARTS`.
+#[cfg(test)]
+pub fn assert_example(day: usize, solutions: &dyn crate::Solver, part: u8) {
+    let input = Puzzle::Example
+        .load(day, part)
+        .unwrap_or_else(|e| panic!("day {}: cannot read the example: {}", day, e));
+    let expected = Expected::load(day)
+        .and_then(|expected| expected.get(day, &Puzzle::Example, part))
+        .unwrap_or_else(|e| panic!("{}", e));
+    let got = solutions
+        .parse(&input)
+        .and_then(|parsed| solutions.solve(part, parsed.as_ref(), &crate::Progress::hidden()));
+    assert_eq!(
+        solutions.implemented(part),
+        expected.is_some(),
+        "day {} part {}: `PARTS` disagrees with the expected example answer",
+        day,
+        part
+    );
+    match expected {
+        Some(answer) => assert_eq!(got, Ok(answer), "day {} part {}", day, part),
+        None => assert_eq!(
+            got,
+            Err(AocError::NotImplemented { day, part }),
+            "day {} part {} has no expected example answer",
+            day,
+            part
+        ),
+    }
+}
+
+/// Generates a test per part checking the example of the enclosing day module, which must
+/// define `Problem`.
+#[macro_export]
+macro_rules! example_tests {
+    ($day:expr) => {
+        #[cfg(test)]
+        mod tests {
+            #[test]
+            fn part1_example() {
+                $crate::expected::assert_example($day, &super::Problem, 1);
+            }
+
+            #[test]
+            fn part2_example() {
+                $crate::expected::assert_example($day, &super::Problem, 2);
+            }
+        }
+    };
+}
@@ -0,0 +1,179 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or an offset on a grid. `x` is the column and `y` the row, growing southward.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Vec2 { x, y }
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<isize> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, rhs: isize) -> Vec2 {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, rhs: Vec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, rhs: Vec2) {
+        *self = *self - rhs;
+    }
+}
+
+impl Add<Direction> for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, rhs: Direction) -> Vec2 {
+        self + rhs.offset()
+    }
+}
+
+/// One of the eight compass directions, north being up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    // Clockwise from north, so that turning is moving along the array.
+    const CLOCKWISE: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    /// North, east, south and west.
+    pub fn all4() -> impl Iterator<Item = Direction> {
+        Self::CLOCKWISE.into_iter().step_by(2)
+    }
+
+    /// Every direction, clockwise from north.
+    pub fn all8() -> impl Iterator<Item = Direction> {
+        Self::CLOCKWISE.into_iter()
+    }
+
+    fn rotate(self, eighths: usize) -> Direction {
+        Self::CLOCKWISE[(self as usize + eighths) % 8]
+    }
+
+    /// A quarter turn counterclockwise.
+    pub fn turn_left(self) -> Direction {
+        self.rotate(6)
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Direction::N => Vec2::new(0, -1),
+            Direction::NE => Vec2::new(1, -1),
+            Direction::E => Vec2::new(1, 0),
+            Direction::SE => Vec2::new(1, 1),
+            Direction::S => Vec2::new(0, 1),
+            Direction::SW => Vec2::new(-1, 1),
+            Direction::W => Vec2::new(-1, 0),
+            Direction::NW => Vec2::new(-1, -1),
+        }
+    }
+
+    /// Reads one of `^ > v <`.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::N),
+            '>' => Some(Direction::E),
+            'v' => Some(Direction::S),
+            '<' => Some(Direction::W),
+            _ => None,
+        }
+    }
+
+    /// The arrow of a cardinal direction, `None` for a diagonal.
+    pub fn to_char(self) -> Option<char> {
+        match self {
+            Direction::N => Some('^'),
+            Direction::E => Some('>'),
+            Direction::S => Some('v'),
+            Direction::W => Some('<'),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns() {
+        assert_eq!(Direction::N.turn_right(), Direction::E);
+        assert_eq!(Direction::N.turn_left(), Direction::W);
+        assert_eq!(Direction::SE.turn_left(), Direction::NE);
+        assert_eq!(Direction::W.reverse(), Direction::E);
+        for direction in Direction::all8() {
+            assert_eq!(
+                direction.offset() + direction.reverse().offset(),
+                Vec2::ZERO
+            );
+        }
+        assert_eq!(Direction::all4().count(), 4);
+        assert!(Direction::all4().all(|d| Direction::from_char(d.to_char().unwrap()) == Some(d)));
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    geometry::{Direction, Vec2},
+    AocError,
+};
+
+/// A rectangular grid, stored row by row in a single `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses one line of text per row, converting every character with `cell`.
+    ///
+    /// The error message returned by `cell` is reported at the offending character. Rows
+    /// of different lengths and empty grids are rejected.
+    pub fn parse(
+        day: usize,
+        input: &str,
+        mut cell: impl FnMut(char) -> Result<T, String>,
+    ) -> Result<Self, AocError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for line in input.lines() {
+            let start = cells.len();
+            for (offset, c) in line.char_indices() {
+                cells.push(
+                    cell(c)
+                        .map_err(|message| AocError::at(day, input, &line[offset..], message))?,
+                );
+            }
+            let row_width = cells.len() - start;
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(AocError::at(
+                    day,
+                    input,
+                    line,
+                    format!("expected {} cells, found {}", width.unwrap(), row_width),
+                ));
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(AocError::invalid(day, "the grid is empty")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, position: Vec2) -> bool {
+        (0..self.width as isize).contains(&position.x)
+            && (0..self.height as isize).contains(&position.y)
+    }
+
+    fn offset(&self, position: Vec2) -> Option<usize> {
+        self.contains(position)
+            .then(|| position.y as usize * self.width + position.x as usize)
+    }
+
+    pub fn get(&self, position: Vec2) -> Option<&T> {
+        self.offset(position).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, position: Vec2) -> Option<&mut T> {
+        self.offset(position).map(|offset| &mut self.cells[offset])
+    }
+
+    /// The horizontal and vertical neighbors of `position` inside the grid.
+    pub fn neighbors4(&self, position: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        Direction::all4()
+            .map(move |direction| position + direction)
+            .filter(|next| self.contains(*next))
+    }
+
+    /// The horizontal, vertical and diagonal neighbors of `position` inside the grid.
+    pub fn neighbors8(&self, position: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        Direction::all8()
+            .map(move |direction| position + direction)
+            .filter(|next| self.contains(*next))
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, j: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(j).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|j| self.column(j))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> {
+        let width = self.width as isize;
+        (0..self.height as isize).flat_map(move |y| (0..width).map(move |x| Vec2::new(x, y)))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The first position holding `value`, row by row.
+    pub fn find(&self, value: &T) -> Option<Vec2>
+    where
+        T: PartialEq,
+    {
+        self.find_all(value).next()
+    }
+
+    /// Every position holding `value`, row by row.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Vec2> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(position, _)| position)
+    }
+}
+
+impl<T> Index<Vec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Vec2) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", position))
+    }
+}
+
+impl<T> IndexMut<Vec2> for Grid<T> {
+    fn index_mut(&mut self, position: Vec2) -> &mut T {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", position))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reports_the_offending_cell() {
+        let error = Grid::parse(0, "12\n3x", |c| {
+            c.to_digit(10)
+                .ok_or_else(|| format!("not a digit: {:?}", c))
+        });
+        assert_eq!(
+            error,
+            Err(AocError::Parse {
+                day: 0,
+                line: 2,
+                column: 2,
+                message: "not a digit: 'x'".to_string(),
+            })
+        );
+        assert!(Grid::parse(0, "ab\nc", Ok).is_err());
+        assert!(Grid::<char>::parse(0, "", Ok).is_err());
+    }
+
+    #[test]
+    fn neighbors_stay_inside() {
+        let grid = Grid::parse(0, "abc\ndef", Ok).unwrap();
+        assert_eq!(
+            grid.neighbors4(Vec2::ZERO).collect::<Vec<_>>(),
+            [Vec2::new(1, 0), Vec2::new(0, 1)]
+        );
+        assert_eq!(grid.neighbors8(Vec2::new(2, 1)).count(), 3);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.find(&'e'), Some(Vec2::new(1, 1)));
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+}
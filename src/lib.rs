@@ -0,0 +1,203 @@
+pub mod answer;
+pub mod error;
+pub mod expected;
+pub mod geometry;
+pub mod grid;
+pub mod parse;
+pub mod progress;
+pub mod shortest_path;
+pub mod toposort;
+use std::{
+    any::Any,
+    convert::Infallible,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+pub use answer::Answer;
+pub use error::AocError;
+pub use progress::Progress;
+
+/// Where the input of a day comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Puzzle {
+    Example,
+    Puzzle,
+    File(PathBuf),
+    Stdin,
+}
+impl Puzzle {
+    // Example and puzzle inputs live next to the day's code, so resolve them from the
+    // crate root rather than from the current working directory.
+    fn day_path(day: usize) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join(format!("day{}", day))
+    }
+
+    /// Reads the input text of `part` for `day`.
+    ///
+    /// A day may ship a part-specific example (`example2.txt`), which takes precedence
+    /// over `example.txt`. Stdin is read once and reused for every part.
+    pub fn load(&self, day: usize, part: u8) -> io::Result<String> {
+        match self {
+            Puzzle::Example => {
+                let part_example = Self::day_path(day).join(format!("example{}.txt", part));
+                if part_example.exists() {
+                    fs::read_to_string(part_example)
+                } else {
+                    fs::read_to_string(Self::day_path(day).join("example.txt"))
+                }
+            }
+            Puzzle::Puzzle => fs::read_to_string(Self::day_path(day).join("puzzle.txt")),
+            Puzzle::File(path) => fs::read_to_string(path),
+            Puzzle::Stdin => {
+                static STDIN: OnceLock<String> = OnceLock::new();
+                if let Some(content) = STDIN.get() {
+                    return Ok(content.clone());
+                }
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content)?;
+                Ok(STDIN.get_or_init(|| content).clone())
+            }
+        }
+    }
+}
+impl FromStr for Puzzle {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "example" => Puzzle::Example,
+            "puzzle" => Puzzle::Puzzle,
+            "-" => Puzzle::Stdin,
+            path => Puzzle::File(PathBuf::from(path)),
+        })
+    }
+}
+/// The two parts of a day. Each `dayN` module implements it on its `Problem`.
+///
+/// The input is parsed once by `parse`, then both parts solve the same parsed value.
+pub trait DaySolutions: Send + Sync {
+    type Parsed: Send + Sync + 'static;
+    /// The parts written so far, the others return `AocError::NotImplemented`.
+    const PARTS: &'static [u8] = &[1, 2];
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError>;
+    /// Long loops report their progress to `progress`.
+    fn part1(&self, parsed: &Self::Parsed, progress: &Progress) -> Result<Answer, AocError>;
+    fn part2(&self, parsed: &Self::Parsed, progress: &Progress) -> Result<Answer, AocError>;
+}
+
+/// Object-safe view of a `DaySolutions`, with the parsed input behind `dyn Any`. This is
+/// what the registry stores. Days and their parsed inputs can be shared between threads.
+pub trait Solver: Send + Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any + Send + Sync>, AocError>;
+    /// Solves `part`, 1 or 2, on a value returned by `parse`.
+    fn solve(&self, part: u8, parsed: &dyn Any, progress: &Progress) -> Result<Answer, AocError>;
+    /// Whether `part` is written, without running it.
+    fn implemented(&self, part: u8) -> bool;
+}
+
+impl<T: DaySolutions> Solver for T {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any + Send + Sync>, AocError> {
+        Ok(Box::new(DaySolutions::parse(self, input)?))
+    }
+
+    fn solve(&self, part: u8, parsed: &dyn Any, progress: &Progress) -> Result<Answer, AocError> {
+        let Some(parsed) = parsed.downcast_ref::<T::Parsed>() else {
+            return Err(AocError::Misuse {
+                message: "the parsed input was not produced by this day".to_string(),
+            });
+        };
+        match part {
+            1 => self.part1(parsed, progress),
+            2 => self.part2(parsed, progress),
+            _ => Err(AocError::Misuse {
+                message: format!("there is no part {}, only parts 1 and 2", part),
+            }),
+        }
+    }
+
+    fn implemented(&self, part: u8) -> bool {
+        T::PARTS.contains(&part)
+    }
+}
+
+/// A registered day.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: usize,
+    pub solutions: &'static dyn Solver,
+}
+
+/// Declares the day modules and registers their `Problem`, so a day only has to be listed
+/// once, in day order.
+macro_rules! days {
+    ($($number:literal => $module:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        static DAYS: &[Day] = &[$(Day {
+            number: $number,
+            solutions: &$module::Problem,
+        }),*];
+    };
+}
+
+days! {
+    1 => day1,
+    2 => day2,
+    3 => day3,
+    4 => day4,
+    5 => day5,
+    6 => day6,
+    7 => day7,
+    8 => day8,
+    9 => day9,
+    10 => day10,
+    11 => day11,
+    12 => day12,
+    13 => day13,
+    14 => day14,
+    15 => day15,
+    16 => day16,
+}
+
+/// Every implemented day, in day order.
+pub fn days() -> &'static [Day] {
+    DAYS
+}
+
+/// The solutions of `day`, if it is implemented.
+pub fn get_day(day: usize) -> Option<&'static dyn Solver> {
+    DAYS.iter()
+        .find(|registered| registered.number == day)
+        .map(|registered| registered.solutions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_rejects_unknown_parts_and_foreign_inputs() {
+        let day = get_day(1).unwrap();
+        let parsed = day.parse("3   4\n").unwrap();
+        let progress = Progress::hidden();
+        assert_eq!(day.solve(1, parsed.as_ref(), &progress), Ok(Answer::Int(1)));
+        for part in [0, 3] {
+            assert!(matches!(
+                day.solve(part, parsed.as_ref(), &progress),
+                Err(AocError::Misuse { .. })
+            ));
+        }
+        let foreign = get_day(2).unwrap().parse("1 2\n").unwrap();
+        assert!(matches!(
+            day.solve(1, foreign.as_ref(), &progress),
+            Err(AocError::Misuse { .. })
+        ));
+    }
+}
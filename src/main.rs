@@ -1,32 +1,21 @@
-pub mod day1;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
-use std::{collections::HashMap, sync::Arc};
-
-use clap::{Parser, Subcommand};
-
-#[derive(Debug, PartialEq, Eq)]
-pub enum Puzzle {
-    Example,
-    Puzzle,
-}
-pub trait DaySolutions {
-    fn part1(&self, puzzle: Puzzle) -> String;
-    fn part2(&self, puzzle: Puzzle) -> String;
-}
+use std::{
+    any::Any,
+    cell::Cell,
+    panic,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use aoc24::{days, expected::Expected, get_day, Answer, AocError, Day, Progress, Puzzle, Solver};
+use clap::{Parser, Subcommand, ValueEnum};
+use rayon::prelude::*;
+use serde_json::{json, Number, Value};
+
+mod bench;
+mod replay;
+mod robots;
+mod scaffold;
+
 #[derive(Debug, Parser)]
 struct Args {
     #[command(subcommand)]
@@ -34,44 +23,563 @@ struct Args {
 }
 #[derive(Debug, Subcommand, Clone)]
 enum Commands {
-    Day { day: usize },
-}
-pub fn get_day(day: usize) -> Option<Arc<dyn DaySolutions>> {
-    let mut days: HashMap<usize, Arc<dyn DaySolutions>> = HashMap::new();
-    days.insert(1, Arc::new(day1::Problem));
-    days.insert(2, Arc::new(day2::Problem));
-    days.insert(3, Arc::new(day3::Problem));
-    days.insert(4, Arc::new(day4::Problem));
-    days.insert(5, Arc::new(day5::Problem));
-    days.insert(6, Arc::new(day6::Problem));
-    days.insert(7, Arc::new(day7::Problem));
-    days.insert(8, Arc::new(day8::Problem));
-    days.insert(9, Arc::new(day9::Problem));
-    days.insert(10, Arc::new(day10::Problem));
-    days.insert(11, Arc::new(day11::Problem));
-    days.insert(12, Arc::new(day12::Problem));
-    days.insert(13, Arc::new(day13::Problem));
-    days.insert(14, Arc::new(day14::Problem));
-    days.insert(15, Arc::new(day15::Problem));
-    days.insert(16, Arc::new(day16::Problem));
-
-    days.get(&day).cloned()
+    Day {
+        day: usize,
+        /// Only run this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Only run on this input: `example`, `puzzle`, a path to an input file or `-` for stdin
+        #[arg(long)]
+        input: Option<Puzzle>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Run both parts of every day on the puzzle input and time them
+    RunAll {
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Run the days and parts concurrently
+        #[arg(long)]
+        parallel: bool,
+    },
+    /// Time the parts of every day, or of one day, on the puzzle input
+    Bench {
+        day: Option<usize>,
+        /// Only time this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Number of runs of each part
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        runs: u32,
+    },
+    /// Create `src/dayN` from the template and register it
+    Scaffold { day: usize },
+    /// Show which days and parts are implemented
+    List,
+    /// Check every day against the answers stored in its `answers.toml`
+    Verify {
+        /// Run the days and parts concurrently
+        #[arg(long)]
+        parallel: bool,
+    },
+    /// Replay the day 15 robot move by move and check the warehouse invariants
+    Replay {
+        /// `example`, `puzzle`, a path to an input file or `-` for stdin
+        #[arg(long, default_value = "example")]
+        input: Puzzle,
+        /// Use the double-width warehouse of part 2
+        #[arg(long)]
+        wide: bool,
+        /// Print the warehouse after this many moves
+        #[arg(long, conflicts_with = "all")]
+        frame: Option<usize>,
+        /// Print the warehouse after every move
+        #[arg(long)]
+        all: bool,
+    },
+    /// Draw the day 14 robots after some seconds, e.g. the candidates of part 2
+    Robots {
+        /// The numbers of seconds to draw the robots after
+        #[arg(required = true)]
+        seconds: Vec<usize>,
+        /// `example`, `puzzle`, a path to an input file or `-` for stdin
+        #[arg(long, default_value = "puzzle")]
+        input: Puzzle,
+    },
 }
-fn main() {
-    let args = Args::parse();
-    let day_value = match args.cmd {
-        Commands::Day { day } => day,
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Human readable output
+    Text,
+    /// One JSON record per line and per part run
+    Json,
+}
+
+#[derive(Debug, Clone)]
+enum Outcome {
+    Solved(Answer),
+    NotImplemented,
+    Failed(AocError),
+    Panicked(String),
+}
+impl Outcome {
+    fn describe(&self) -> String {
+        match self {
+            Outcome::Solved(answer) => answer.to_string(),
+            Outcome::NotImplemented => "not implemented".to_string(),
+            Outcome::Failed(error) => format!("error: {}", error),
+            Outcome::Panicked(message) => format!("panicked: {}", message),
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match self {
+            Outcome::Solved(_) => "solved",
+            Outcome::NotImplemented => "not_implemented",
+            Outcome::Failed(_) => "failed",
+            Outcome::Panicked(_) => "panicked",
+        }
+    }
+}
+
+// Integers too large for JSON numbers are written as strings, and a not applicable answer
+// as null.
+fn answer_json(answer: &Answer) -> Value {
+    match answer {
+        Answer::Int(n) => {
+            Number::from_i128(*n).map_or_else(|| Value::String(n.to_string()), Value::Number)
+        }
+        Answer::Str(s) => Value::String(s.clone()),
+        Answer::Multi(answers) => Value::Array(answers.iter().map(answer_json).collect()),
+        Answer::NotApplicable => Value::Null,
+    }
+}
+
+fn print_record(day_value: usize, part: u8, puzzle: &Puzzle, outcome: &Outcome, elapsed: Duration) {
+    let input = match puzzle {
+        Puzzle::Example => "example",
+        Puzzle::Puzzle => "puzzle",
+        Puzzle::File(_) => "file",
+        Puzzle::Stdin => "stdin",
+    };
+    let (answer, error) = match outcome {
+        Outcome::Solved(answer) => (answer_json(answer), Value::Null),
+        Outcome::NotImplemented => (Value::Null, Value::Null),
+        Outcome::Failed(error) => (Value::Null, Value::String(error.to_string())),
+        Outcome::Panicked(message) => (Value::Null, Value::String(message.clone())),
     };
+    let record = json!({
+        "day": day_value,
+        "part": part,
+        "input": input,
+        "status": outcome.status(),
+        "answer": answer,
+        "duration_ms": elapsed.as_secs_f64() * 1000.0,
+        "error": error,
+    });
+    println!("{}", record);
+}
+
+thread_local! {
+    static GUARDED: Cell<bool> = const { Cell::new(false) };
+}
+// The `guard` calls running, whose solvers may panic on rayon worker threads too.
+static GUARDS: AtomicUsize = AtomicUsize::new(0);
+
+// Silences the panics `guard` catches and reports, the default hook would print each one
+// with its backtrace. Other panics are printed as usual.
+fn install_panic_hook() {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let guarded = GUARDED.with(Cell::get)
+            || (rayon::current_thread_index().is_some() && GUARDS.load(Ordering::SeqCst) > 0);
+        if !guarded {
+            default(info);
+        }
+    }));
+}
+
+// Runs one solver call, turning errors, `unimplemented!()`/`todo!()` and other panics into
+// an `Outcome` instead of aborting the whole command.
+fn guard<T>(call: impl FnOnce() -> Result<T, AocError>) -> Result<T, Outcome> {
+    let outer = GUARDED.with(|guarded| guarded.replace(true));
+    GUARDS.fetch_add(1, Ordering::SeqCst);
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(call));
+    GUARDS.fetch_sub(1, Ordering::SeqCst);
+    GUARDED.with(|guarded| guarded.set(outer));
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(AocError::NotImplemented { .. })) => Err(Outcome::NotImplemented),
+        Ok(Err(error)) => Err(Outcome::Failed(error)),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            if message.starts_with("not implemented") || message.starts_with("not yet implemented")
+            {
+                Err(Outcome::NotImplemented)
+            } else {
+                Err(Outcome::Panicked(message))
+            }
+        }
+    }
+}
+
+fn load(day_value: usize, part: u8, puzzle: &Puzzle) -> Result<String, Outcome> {
+    puzzle.load(day_value, part).map_err(|e| {
+        Outcome::Failed(AocError::Io {
+            day: day_value,
+            message: e.to_string(),
+        })
+    })
+}
+
+fn solve(day: &dyn Solver, part: u8, parsed: &dyn Any, progress: &Progress) -> Outcome {
+    match guard(|| day.solve(part, parsed, progress)) {
+        Ok(answer) => Outcome::Solved(answer),
+        Err(outcome) => outcome,
+    }
+}
+
+type ParseResult = Result<Box<dyn Any + Send + Sync>, Outcome>;
 
-    if let Some(day) = get_day(day_value) {
-        println!("Day {}:", day_value);
-        println!("----PART 1----");
-        println!("Example: {}", day.part1(Puzzle::Example));
-        println!("Solution: {}", day.part1(Puzzle::Puzzle));
-        println!("----PART 2----");
-        println!("Example: {}", day.part2(Puzzle::Example));
-        println!("Solution: {}", day.part2(Puzzle::Puzzle));
+// Maps `items` in order, concurrently when `parallel` is set.
+fn map_ordered<T, R>(items: &[T], parallel: bool, f: impl Fn(&T) -> R + Sync + Send) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    if parallel {
+        items.par_iter().map(f).collect()
     } else {
-        println!("Day {} is not implemented yet.", day_value);
+        items.iter().map(f).collect()
+    }
+}
+
+// Solves `parts` on `puzzle`, parsing the input once when the parts share it. Each part is
+// timed, and the parsing time goes to the first part using that input.
+fn run_parts(
+    day: &dyn Solver,
+    day_value: usize,
+    parts: &[u8],
+    puzzle: &Puzzle,
+    parallel: bool,
+    progress: &Progress,
+) -> Vec<(Outcome, Duration)> {
+    let inputs = parts
+        .iter()
+        .map(|&part| load(day_value, part, puzzle))
+        .collect::<Vec<_>>();
+    // index of the first part reading the same input
+    let first = (0..parts.len())
+        .map(|i| {
+            (0..i)
+                .find(|&j| matches!((&inputs[i], &inputs[j]), (Ok(a), Ok(b)) if a == b))
+                .unwrap_or(i)
+        })
+        .collect::<Vec<_>>();
+    let indices = (0..parts.len()).collect::<Vec<_>>();
+    let parsed: Vec<Option<(ParseResult, Duration)>> = map_ordered(&indices, parallel, |&i| {
+        let input = inputs[i].as_ref().ok().filter(|_| first[i] == i)?;
+        let start = Instant::now();
+        let result = guard(|| day.parse(input));
+        Some((result, start.elapsed()))
+    });
+    map_ordered(&indices, parallel, |&i| {
+        if let Err(outcome) = &inputs[i] {
+            return (outcome.clone(), Duration::ZERO);
+        }
+        let (result, parse_time) = parsed[first[i]].as_ref().unwrap();
+        let parse_time = if first[i] == i {
+            *parse_time
+        } else {
+            Duration::ZERO
+        };
+        let start = Instant::now();
+        let outcome = match result {
+            Ok(parsed) => solve(day, parts[i], parsed.as_ref(), progress),
+            Err(outcome) => outcome.clone(),
+        };
+        (outcome, parse_time + start.elapsed())
+    })
+}
+
+fn run_day(day_value: usize, part: Option<u8>, input: Option<Puzzle>, format: Format) -> bool {
+    let parts = match part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let inputs = match input {
+        Some(i) => vec![i],
+        None => vec![Puzzle::Example, Puzzle::Puzzle],
+    };
+    let Some(day) = get_day(day_value) else {
+        match format {
+            Format::Text => println!("Day {} is not implemented yet.", day_value),
+            // a record for each part, the same as a registered day with nothing implemented
+            Format::Json => {
+                for &part in parts.iter() {
+                    for puzzle in inputs.iter() {
+                        let outcome = Outcome::NotImplemented;
+                        print_record(day_value, part, puzzle, &outcome, Duration::ZERO);
+                    }
+                }
+            }
+        }
+        return true;
+    };
+
+    let progress = Progress::stderr();
+    let outcomes = inputs
+        .iter()
+        .map(|puzzle| run_parts(day, day_value, &parts, puzzle, false, &progress))
+        .collect::<Vec<_>>();
+
+    if format == Format::Json {
+        for (i, &part) in parts.iter().enumerate() {
+            for (puzzle, outcomes) in inputs.iter().zip(outcomes.iter()) {
+                let (outcome, elapsed) = &outcomes[i];
+                print_record(day_value, part, puzzle, outcome, *elapsed);
+            }
+        }
+        return outcomes
+            .iter()
+            .flatten()
+            .all(|(outcome, _)| !matches!(outcome, Outcome::Failed(_) | Outcome::Panicked(_)));
+    }
+
+    let mut solved = 0;
+    let mut not_implemented = 0;
+    let mut failed = 0;
+    println!("Day {}:", day_value);
+    for (i, part) in parts.iter().enumerate() {
+        println!("----PART {}----", part);
+        for (puzzle, outcomes) in inputs.iter().zip(outcomes.iter()) {
+            let label = match puzzle {
+                Puzzle::Example => "Example",
+                Puzzle::Puzzle => "Solution",
+                Puzzle::File(_) => "File",
+                Puzzle::Stdin => "Stdin",
+            };
+            let (outcome, _) = &outcomes[i];
+            match outcome {
+                Outcome::Solved(_) => solved += 1,
+                Outcome::NotImplemented => not_implemented += 1,
+                Outcome::Failed(_) | Outcome::Panicked(_) => failed += 1,
+            }
+            println!("{}: {}", label, outcome.describe());
+        }
+    }
+    println!("----SUMMARY----");
+    println!(
+        "{} solved, {} not implemented, {} failed",
+        solved, not_implemented, failed
+    );
+    failed == 0
+}
+
+fn run_all(format: Format, parallel: bool) -> bool {
+    let progress = Progress::stderr();
+    let start = Instant::now();
+    let outcomes = map_ordered(days(), parallel, |day| {
+        run_parts(
+            day.solutions,
+            day.number,
+            &[1, 2],
+            &Puzzle::Puzzle,
+            parallel,
+            &progress,
+        )
+    });
+    let wall_time = start.elapsed();
+
+    let mut rows = vec![];
+    let mut total = Duration::ZERO;
+    let mut success = true;
+    for (day, outcomes) in days().iter().zip(outcomes) {
+        let day_value = day.number;
+        for (part, (outcome, elapsed)) in [1, 2].into_iter().zip(outcomes) {
+            total += elapsed;
+            if let Outcome::Failed(_) | Outcome::Panicked(_) = outcome {
+                success = false;
+            }
+            if format == Format::Json {
+                print_record(day_value, part, &Puzzle::Puzzle, &outcome, elapsed);
+                continue;
+            }
+            rows.push((day_value, part, outcome.describe(), elapsed));
+        }
+    }
+    if format == Format::Json {
+        return success;
+    }
+
+    let answer_width = rows
+        .iter()
+        .map(|(_, _, answer, _)| answer.len())
+        .chain(["Answer".len()])
+        .max()
+        .unwrap();
+    let separator = format!("+-----+------+-{}-+------------+", "-".repeat(answer_width));
+    println!("{}", separator);
+    println!("| Day | Part | {:<answer_width$} |       Time |", "Answer");
+    println!("{}", separator);
+    for (day_value, part, answer, elapsed) in rows.iter() {
+        println!(
+            "| {:>3} | {:>4} | {:<answer_width$} | {:>10} |",
+            day_value,
+            part,
+            answer,
+            format!("{:.2?}", elapsed)
+        );
+    }
+    println!("{}", separator);
+    println!(
+        "| {:<total_width$} | {:>10} |",
+        "Total",
+        format!("{:.2?}", total),
+        total_width = answer_width + 13
+    );
+    if parallel {
+        println!(
+            "| {:<total_width$} | {:>10} |",
+            "Wall clock",
+            format!("{:.2?}", wall_time),
+            total_width = answer_width + 13
+        );
+    }
+    println!("{}", separator);
+    success
+}
+
+// A part counts as implemented unless it reports itself as not implemented on the example.
+fn list() {
+    let separator = "+-----+--------+--------+";
+    println!("{}", separator);
+    println!("| Day | Part 1 | Part 2 |");
+    println!("{}", separator);
+    for day in days() {
+        let [part1, part2] = [1, 2].map(|part| match day.solutions.implemented(part) {
+            true => "yes",
+            false => "no",
+        });
+        println!("| {:>3} | {:<6} | {:<6} |", day.number, part1, part2);
+    }
+    println!("{}", separator);
+}
+
+#[derive(Default)]
+struct Verification {
+    ok: usize,
+    mismatched: usize,
+    unchecked: usize,
+    // mismatches and errors, in order
+    messages: Vec<String>,
+}
+
+fn verify_day(day: &Day, parallel: bool, progress: &Progress) -> Verification {
+    let day_value = day.number;
+    let mut verification = Verification::default();
+    let expected = match Expected::load(day_value) {
+        Ok(expected) => expected,
+        Err(e) => {
+            verification.mismatched += 1;
+            verification
+                .messages
+                .push(format!("Day {:>2}: {}", day_value, e));
+            return verification;
+        }
+    };
+    for (puzzle, label) in [(Puzzle::Example, "example"), (Puzzle::Puzzle, "puzzle")] {
+        let mut checked = vec![];
+        for part in [1, 2] {
+            match expected.get(day_value, &puzzle, part) {
+                Ok(Some(answer)) => checked.push((part, answer)),
+                Ok(None) => verification.unchecked += 1,
+                Err(e) => {
+                    verification.mismatched += 1;
+                    verification.messages.push(format!(
+                        "Day {:>2} part {} {:<7}: {}",
+                        day_value, part, label, e
+                    ));
+                }
+            }
+        }
+        let parts = checked.iter().map(|(part, _)| *part).collect::<Vec<_>>();
+        let outcomes = run_parts(
+            day.solutions,
+            day_value,
+            &parts,
+            &puzzle,
+            parallel,
+            progress,
+        );
+        for ((part, answer), (outcome, _)) in checked.into_iter().zip(outcomes) {
+            if let Outcome::Solved(got) = &outcome {
+                if *got == answer {
+                    verification.ok += 1;
+                    continue;
+                }
+            }
+            verification.mismatched += 1;
+            verification.messages.push(format!(
+                "Day {:>2} part {} {:<7}: expected {}, got {}",
+                day_value,
+                part,
+                label,
+                answer,
+                outcome.describe()
+            ));
+        }
+    }
+    verification
+}
+
+fn verify(parallel: bool) -> bool {
+    let mut ok = 0;
+    let mut mismatched = 0;
+    let mut unchecked = 0;
+    let progress = Progress::stderr();
+    for verification in map_ordered(days(), parallel, |day| verify_day(day, parallel, &progress)) {
+        for message in verification.messages {
+            println!("{}", message);
+        }
+        ok += verification.ok;
+        mismatched += verification.mismatched;
+        unchecked += verification.unchecked;
+    }
+    println!("----SUMMARY----");
+    println!(
+        "{} ok, {} mismatched, {} without an expected answer",
+        ok, mismatched, unchecked
+    );
+    mismatched == 0
+}
+
+fn main() {
+    let args = Args::parse();
+
+    install_panic_hook();
+
+    let success = match args.cmd {
+        Commands::Day {
+            day,
+            part,
+            input,
+            format,
+        } => run_day(day, part, input, format),
+        Commands::RunAll { format, parallel } => run_all(format, parallel),
+        Commands::Bench { day, part, runs } => bench::bench(day, part, runs),
+        Commands::Scaffold { day } => match scaffold::scaffold(day) {
+            Ok(()) => {
+                println!(
+                    "Created src/day{}, fill in its example and puzzle inputs.",
+                    day
+                );
+                true
+            }
+            Err(e) => {
+                println!("Cannot scaffold day {}: {}", day, e);
+                false
+            }
+        },
+        Commands::List => {
+            list();
+            true
+        }
+        Commands::Verify { parallel } => verify(parallel),
+        Commands::Replay {
+            input,
+            wide,
+            frame,
+            all,
+        } => replay::replay(&input, wide, frame, all),
+        Commands::Robots { seconds, input } => robots::robots(&input, &seconds),
+    };
+    if !success {
+        std::process::exit(1);
     }
 }
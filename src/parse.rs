@@ -0,0 +1,271 @@
+//! Helpers for the days' parsers. The text they are given must be a slice of `input`, so
+//! that their errors point at the right line and column.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{error::parse_token, grid::Grid, AocError};
+
+/// Every integer in `text`, in order, skipping whatever separates them. A `-` right before
+/// the digits makes the integer negative. Being lenient, this suits the lines mixing text
+/// and numbers; `words` and `separated` reject anything unexpected.
+pub fn ints<T>(day: usize, input: &str, text: &str) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = text.as_bytes();
+    let mut ints = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = if i > 0 && bytes[i - 1] == b'-' {
+            i - 1
+        } else {
+            i
+        };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        ints.push(parse_token(day, input, &text[start..i])?);
+    }
+    Ok(ints)
+}
+
+/// Exactly `N` integers from `text`, `what` describing the expected text in the error.
+pub fn n_ints<T, const N: usize>(
+    day: usize,
+    input: &str,
+    text: &str,
+    what: &str,
+) -> Result<[T; N], AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    exactly(day, input, text, what, ints(day, input, text)?)
+}
+
+/// Every whitespace separated value of `text`, each of them parsed as a whole.
+pub fn words<T>(day: usize, input: &str, text: &str) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.split_whitespace()
+        .map(|word| parse_token(day, input, word))
+        .collect()
+}
+
+/// Exactly `N` whitespace separated values from `text`, `what` describing the expected text
+/// in the error.
+pub fn n_words<T, const N: usize>(
+    day: usize,
+    input: &str,
+    text: &str,
+    what: &str,
+) -> Result<[T; N], AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    exactly(day, input, text, what, words(day, input, text)?)
+}
+
+/// The values of `text` between each `separator`, each of them parsed as a whole once
+/// trimmed. There is always at least one, an empty `text` is an error.
+pub fn separated<T>(
+    day: usize,
+    input: &str,
+    text: &str,
+    separator: char,
+) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.split(separator)
+        .map(|value| parse_token(day, input, value.trim()))
+        .collect()
+}
+
+/// Exactly `N` values of `text` between each `separator`, `what` describing the expected text
+/// in the error.
+pub fn n_separated<T, const N: usize>(
+    day: usize,
+    input: &str,
+    text: &str,
+    separator: char,
+    what: &str,
+) -> Result<[T; N], AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    exactly(
+        day,
+        input,
+        text,
+        what,
+        separated(day, input, text, separator)?,
+    )
+}
+
+fn exactly<T, const N: usize>(
+    day: usize,
+    input: &str,
+    text: &str,
+    what: &str,
+    values: Vec<T>,
+) -> Result<[T; N], AocError> {
+    values
+        .try_into()
+        .map_err(|_| AocError::at(day, input, text, format!("expected {}", what)))
+}
+
+/// The blocks of `input` separated by blank lines, without their surrounding newlines.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|section| section.trim_matches('\n'))
+        .filter(|section| !section.trim().is_empty())
+}
+
+/// The two blocks of `input` separated by a blank line, `what` describing them in the
+/// error, which points at a third block or at the end of an input missing one.
+pub fn two_sections<'a>(
+    day: usize,
+    input: &'a str,
+    what: &str,
+) -> Result<(&'a str, &'a str), AocError> {
+    let mut blocks = sections(input);
+    let message = format!("expected {} separated by a blank line", what);
+    match (blocks.next(), blocks.next(), blocks.next()) {
+        (Some(first), Some(second), None) => Ok((first, second)),
+        (Some(_), Some(_), Some(third)) => Err(AocError::at(
+            day,
+            input,
+            third,
+            format!("{}, found a third block", message),
+        )),
+        _ => Err(AocError::at(day, input, &input[input.len()..], message)),
+    }
+}
+
+/// A `key: value value ...` line.
+pub fn key_values<K, V>(day: usize, input: &str, line: &str) -> Result<(K, Vec<V>), AocError>
+where
+    K: FromStr,
+    K::Err: Display,
+    V: FromStr,
+    V::Err: Display,
+{
+    let Some((key, values)) = line.split_once(':') else {
+        return Err(AocError::at(day, input, line, "expected `<key>: <values>`"));
+    };
+    Ok((
+        parse_token(day, input, key.trim())?,
+        values
+            .split_whitespace()
+            .map(|value| parse_token(day, input, value))
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+/// A grid of the characters of `input`.
+pub fn char_grid(day: usize, input: &str) -> Result<Grid<char>, AocError> {
+    Grid::parse(day, input, Ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ints_keep_their_sign() {
+        let input = "p=0,4 v=3,-3\nButton A: X+94, Y+34";
+        assert_eq!(ints::<i32>(0, input, input), Ok(vec![0, 4, 3, -3, 94, 34]));
+        assert!(matches!(
+            ints::<u8>(0, input, input),
+            Err(AocError::Parse {
+                line: 1,
+                column: 11,
+                ..
+            })
+        ));
+        let error = n_ints::<i32, 3>(0, input, &input[13..], "three numbers");
+        assert!(matches!(
+            error,
+            Err(AocError::Parse {
+                line: 2,
+                column: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn words_and_separated_are_strict() {
+        let input = "7 6 x 2\n47-53\n75,,29";
+        assert!(matches!(
+            words::<i32>(0, input, &input[..7]),
+            Err(AocError::Parse {
+                line: 1,
+                column: 5,
+                ..
+            })
+        ));
+        assert!(matches!(
+            n_separated::<i32, 2>(0, input, &input[8..13], '|', "a rule"),
+            Err(AocError::Parse {
+                line: 2,
+                column: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            separated::<i32>(0, input, &input[14..], ','),
+            Err(AocError::Parse {
+                line: 3,
+                column: 4,
+                ..
+            })
+        ));
+        assert_eq!(
+            n_words::<i32, 2>(0, "3   4", "3   4", "two IDs"),
+            Ok([3, 4])
+        );
+        assert_eq!(
+            separated::<i32>(0, "75, 29", "75, 29", ','),
+            Ok(vec![75, 29])
+        );
+    }
+
+    #[test]
+    fn sections_and_key_values() {
+        let input = "a\nb\n\nc\n";
+        assert_eq!(two_sections(0, input, "two blocks"), Ok(("a\nb", "c")));
+        assert!(matches!(
+            two_sections(0, "a\nb\n", "two blocks"),
+            Err(AocError::Parse {
+                line: 3,
+                column: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            two_sections(0, "a\n\nb\n\n\nc", "two blocks"),
+            Err(AocError::Parse {
+                line: 6,
+                column: 1,
+                ..
+            })
+        ));
+        assert_eq!(
+            key_values::<i64, i64>(0, "190: 10 19", "190: 10 19"),
+            Ok((190, vec![10, 19]))
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// Where solvers report the progress of their long loops.
+///
+/// The CLI draws it as indicatif bars on stderr. `Progress::hidden()`, used by the tests and
+/// the benchmarks, draws nothing.
+#[derive(Clone)]
+pub struct Progress {
+    bars: MultiProgress,
+}
+
+impl Progress {
+    pub fn hidden() -> Self {
+        Progress {
+            bars: MultiProgress::with_draw_target(ProgressDrawTarget::hidden()),
+        }
+    }
+
+    /// Draws on stderr, unless it is not a terminal.
+    pub fn stderr() -> Self {
+        Progress {
+            bars: MultiProgress::new(),
+        }
+    }
+
+    /// A bar of `len` steps, removed once dropped.
+    pub fn bar(&self, message: &str, len: u64) -> Bar {
+        let bar = ProgressBar::new(len)
+            .with_style(
+                ProgressStyle::with_template("{msg} [{bar:40}] {pos}/{len} ({eta})")
+                    .unwrap()
+                    .progress_chars("=> "),
+            )
+            .with_message(message.to_string());
+        Bar(self.bars.add(bar))
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Progress::hidden()
+    }
+}
+
+pub struct Bar(ProgressBar);
+
+impl Bar {
+    pub fn inc(&self, delta: u64) {
+        self.0.inc(delta);
+    }
+}
+
+impl Drop for Bar {
+    fn drop(&mut self) {
+        self.0.finish_and_clear();
+    }
+}
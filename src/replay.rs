@@ -0,0 +1,45 @@
+use aoc24::{day15, DaySolutions, Puzzle};
+
+use crate::{load, Outcome};
+
+/// Replays the day 15 robot on `input`, prints the requested frames and checks the
+/// warehouse invariants after every move.
+pub fn replay(input: &Puzzle, wide: bool, frame: Option<usize>, all: bool) -> bool {
+    let part = if wide { 2 } else { 1 };
+    let warehouse = load(15, part, input)
+        .and_then(|input| DaySolutions::parse(&day15::Problem, &input).map_err(Outcome::Failed));
+    let warehouse = match warehouse {
+        Ok(warehouse) if wide => warehouse.widen(),
+        Ok(warehouse) => warehouse,
+        Err(outcome) => {
+            println!("{}", outcome.describe());
+            return false;
+        }
+    };
+    let replay = warehouse.replay();
+
+    if all {
+        for (i, state) in replay.frames().enumerate() {
+            println!("----FRAME {}----\n{}\n", i, state);
+        }
+    } else if let Some(i) = frame {
+        match replay.frame(i) {
+            Some(state) => println!("----FRAME {}----\n{}\n", i, state),
+            None => {
+                println!("There are only {} frames.", replay.len());
+                return false;
+            }
+        }
+    }
+
+    match replay.check() {
+        Ok(()) => {
+            println!("{} moves, every invariant holds.", replay.len() - 1);
+            true
+        }
+        Err(e) => {
+            println!("error: {}", e);
+            false
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use aoc24::{day14, DaySolutions, Puzzle};
+
+use crate::{load, Outcome};
+
+/// Draws the day 14 robots on `input` after each of `seconds`, to look for the tree among
+/// the candidates of part 2.
+pub fn robots(input: &Puzzle, seconds: &[usize]) -> bool {
+    let space = load(14, 2, input)
+        .and_then(|input| DaySolutions::parse(&day14::Problem, &input).map_err(Outcome::Failed));
+    let space = match space {
+        Ok(space) => space,
+        Err(outcome) => {
+            println!("{}", outcome.describe());
+            return false;
+        }
+    };
+    for &k in seconds {
+        println!("----AFTER {} SECONDS----\n{}\n", k, space.draw(k));
+    }
+    true
+}
@@ -0,0 +1,168 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// Replaced by the day number in every file of the template.
+const PLACEHOLDER: &str = "__DAY__";
+
+fn src_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Creates `src/dayN` from `src/template_day` and registers it in the `days!` block of
+/// `lib.rs`.
+pub fn scaffold(day: usize) -> io::Result<()> {
+    scaffold_in(&src_path(), day)
+}
+
+// `scaffold` in the `src` directory of any copy of the crate.
+fn scaffold_in(src: &Path, day: usize) -> io::Result<()> {
+    if !(1..=25).contains(&day) {
+        return Err(invalid(format!("{} is not an Advent of Code day", day)));
+    }
+    let day_dir = src.join(format!("day{}", day));
+    if day_dir.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", day_dir.display()),
+        ));
+    }
+    // check the registry before writing anything
+    let lib_path = src.join("lib.rs");
+    let lib = register(&fs::read_to_string(&lib_path)?, day)?;
+
+    fs::create_dir(&day_dir)?;
+    for entry in fs::read_dir(src.join("template_day"))? {
+        let entry = entry?;
+        let content = fs::read_to_string(entry.path())?.replace(PLACEHOLDER, &day.to_string());
+        fs::write(day_dir.join(entry.file_name()), content)?;
+    }
+    fs::write(lib_path, lib)
+}
+
+// Inserts `N => dayN,` into the `days!` block of `lib`, keeping the block in day order.
+fn register(lib: &str, day: usize) -> io::Result<String> {
+    let header = "days! {\n";
+    let start = lib
+        .find(header)
+        .ok_or_else(|| invalid("lib.rs has no `days!` block".to_string()))?
+        + header.len();
+    let end = start
+        + lib[start..]
+            .find("}\n")
+            .ok_or_else(|| invalid("the `days!` block of lib.rs is not closed".to_string()))?;
+
+    let mut insert_at = end;
+    let mut offset = start;
+    for line in lib[start..end].split_inclusive('\n') {
+        let number = line
+            .split_once("=>")
+            .and_then(|(number, _)| number.trim().parse::<usize>().ok())
+            .ok_or_else(|| invalid(format!("unexpected line in `days!`: {:?}", line.trim())))?;
+        if number == day {
+            return Err(invalid(format!("day {} is already registered", day)));
+        }
+        if number > day {
+            insert_at = offset;
+            break;
+        }
+        offset += line.len();
+    }
+    Ok(format!(
+        "{}    {} => day{},\n{}",
+        &lib[..insert_at],
+        day,
+        day,
+        &lib[insert_at..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    const LIB: &str = "days! {\n    1 => day1,\n    3 => day3,\n}\n";
+
+    #[test]
+    fn register_keeps_the_day_order() {
+        assert_eq!(
+            register(LIB, 2).unwrap(),
+            "days! {\n    1 => day1,\n    2 => day2,\n    3 => day3,\n}\n"
+        );
+        assert_eq!(
+            register(LIB, 4).unwrap(),
+            "days! {\n    1 => day1,\n    3 => day3,\n    4 => day4,\n}\n"
+        );
+        assert!(register(LIB, 3)
+            .unwrap_err()
+            .to_string()
+            .contains("already registered"));
+    }
+
+    #[test]
+    fn register_rejects_a_malformed_block() {
+        for lib in [
+            "mod day1;\n",
+            "days! {\n    1 => day1,\n",
+            "days! {\n    1 => day1,\n    day3,\n}\n",
+        ] {
+            let error = register(lib, 2).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{:?}", lib);
+        }
+    }
+
+    // Scaffolds a day in a copy of the crate and type-checks it, so that the template keeps
+    // up with the library.
+    #[test]
+    fn scaffolded_day_compiles() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let check = root.join("target").join("scaffold-check");
+        let copy = check.join("crate");
+        if copy.exists() {
+            fs::remove_dir_all(&copy).unwrap();
+        }
+        copy_dir(&root.join("src"), &copy.join("src")).unwrap();
+        for file in ["Cargo.toml", "Cargo.lock"] {
+            if root.join(file).exists() {
+                fs::copy(root.join(file), copy.join(file)).unwrap();
+            }
+        }
+        let day = (1..=25)
+            .find(|day| !root.join("src").join(format!("day{}", day)).exists())
+            .unwrap();
+        scaffold_in(&copy.join("src"), day).unwrap();
+
+        let output = Command::new(env!("CARGO"))
+            .args(["check", "--offline", "--all-targets", "--quiet"])
+            .env("CARGO_TARGET_DIR", check.join("target"))
+            .current_dir(&copy)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "day {} does not compile:\n{}",
+            day,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+            } else {
+                fs::copy(entry.path(), to.join(entry.file_name()))?;
+            }
+        }
+        Ok(())
+    }
+}
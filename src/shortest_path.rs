@@ -0,0 +1,132 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+};
+
+/// A weighted directed graph, described by the successors of each node.
+pub trait Graph {
+    type Node: Clone + Eq + Hash + Ord;
+
+    /// The nodes reachable in one step from `node`, with the cost of that step.
+    fn successors(&self, node: &Self::Node) -> Vec<(Self::Node, usize)>;
+}
+
+/// The result of `dijkstra`: the distance from the starts to every reachable node, and for
+/// each node the predecessors through which it is reached at that distance.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<N> {
+    pub distances: HashMap<N, usize>,
+    pub predecessors: HashMap<N, Vec<N>>,
+}
+
+/// Explores every node reachable from `starts`, cheapest first.
+pub fn dijkstra<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+) -> ShortestPaths<G::Node> {
+    let mut distances = HashMap::new();
+    let mut predecessors: HashMap<G::Node, Vec<G::Node>> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        distances.insert(start.clone(), 0);
+        queue.push(Reverse((0, start)));
+    }
+    while let Some(Reverse((distance, node))) = queue.pop() {
+        if distances[&node] < distance {
+            // already settled through a shorter path
+            continue;
+        }
+        for (next, cost) in graph.successors(&node) {
+            let next_distance = distance + cost;
+            match distances.get(&next) {
+                Some(&known) if known < next_distance => {}
+                Some(&known) if known == next_distance => {
+                    predecessors.entry(next).or_default().push(node.clone());
+                }
+                _ => {
+                    distances.insert(next.clone(), next_distance);
+                    predecessors.insert(next.clone(), vec![node.clone()]);
+                    queue.push(Reverse((next_distance, next)));
+                }
+            }
+        }
+    }
+    ShortestPaths {
+        distances,
+        predecessors,
+    }
+}
+
+impl<N: Clone + Eq + Hash> ShortestPaths<N> {
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    /// The closest of `targets` that is reachable, with its distance.
+    pub fn closest<'a>(&self, targets: impl IntoIterator<Item = &'a N>) -> Option<(&'a N, usize)>
+    where
+        N: 'a,
+    {
+        targets
+            .into_iter()
+            .filter_map(|target| Some((target, self.distance(target)?)))
+            .min_by_key(|(_, distance)| *distance)
+    }
+
+    /// Every node on a shortest path from the starts to the closest of `targets`. Ties
+    /// between targets keep all of them.
+    pub fn nodes_on_paths<'a>(&self, targets: impl IntoIterator<Item = &'a N>) -> HashSet<N>
+    where
+        N: 'a,
+    {
+        let targets: Vec<&N> = targets.into_iter().collect();
+        let Some((_, best)) = self.closest(targets.iter().copied()) else {
+            return HashSet::new();
+        };
+        let mut on_paths = HashSet::new();
+        let mut to_visit: Vec<N> = targets
+            .into_iter()
+            .filter(|target| self.distance(target) == Some(best))
+            .cloned()
+            .collect();
+        while let Some(node) = to_visit.pop() {
+            if !on_paths.insert(node.clone()) {
+                continue;
+            }
+            if let Some(predecessors) = self.predecessors.get(&node) {
+                to_visit.extend(predecessors.iter().cloned());
+            }
+        }
+        on_paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 3 and 0 -> 2 -> 3 both cost 2, 0 -> 3 costs 5.
+    struct Diamond;
+
+    impl Graph for Diamond {
+        type Node = u8;
+
+        fn successors(&self, node: &u8) -> Vec<(u8, usize)> {
+            match node {
+                0 => vec![(1, 1), (2, 1), (3, 5)],
+                1 | 2 => vec![(3, 1)],
+                _ => vec![],
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_every_shortest_path() {
+        let paths = dijkstra(&Diamond, [0]);
+        assert_eq!(paths.distance(&3), Some(2));
+        assert_eq!(paths.predecessors[&3].len(), 2);
+        assert_eq!(paths.nodes_on_paths(&[3]), HashSet::from([0, 1, 2, 3]));
+        assert_eq!(paths.nodes_on_paths(&[1, 3]), HashSet::from([0, 1]));
+    }
+}
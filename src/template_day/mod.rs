@@ -1,41 +1,29 @@
-use std::fs;
-
-use crate::{DaySolutions, Puzzle};
+use crate::{Answer, AocError, DaySolutions, Progress};
 
 #[derive(Clone)]
 pub struct Problem;
 
-fn load_problem(puzzle: Puzzle) -> String {
-    let content = match puzzle {
-        //TODO: change the directory name
-        Puzzle::Example => fs::read_to_string("src/dayX/example.txt").unwrap(),
-        Puzzle::Puzzle => fs::read_to_string("src/dayX/puzzle.txt").unwrap(),
-    };
-    content
-}
+const DAY: usize = __DAY__;
 
-fn parse_pb(input: &str) -> Vec<&str> {
-    todo!()
+fn parse_pb(input: &str) -> Result<Vec<String>, AocError> {
+    Ok(input.lines().map(String::from).collect())
 }
 
-
 impl DaySolutions for Problem {
-    fn part1(&self, puzzle: Puzzle) -> String {
-        if puzzle == Puzzle::Puzzle {
-            unimplemented!()
-        }
-        let input = load_problem(puzzle);
-        let mut result = 0;
-        format!("{:?}", result)
+    type Parsed = Vec<String>;
+    const PARTS: &'static [u8] = &[];
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_pb(input)
     }
 
-    fn part2(&self, puzzle: Puzzle) -> String {
-        if puzzle == Puzzle::Puzzle {
-            unimplemented!()
-        }
-        let input = load_problem(puzzle);
-        let pb = parse_pb(&input);
-        let mut result = 0;
-        format!("{:?}", result)
+    fn part1(&self, _pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
+        Err(AocError::NotImplemented { day: DAY, part: 1 })
+    }
+
+    fn part2(&self, _pb: &Self::Parsed, _progress: &Progress) -> Result<Answer, AocError> {
+        Err(AocError::NotImplemented { day: DAY, part: 2 })
     }
 }
+
+crate::example_tests!(__DAY__);
@@ -0,0 +1,104 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::Display,
+    hash::Hash,
+};
+
+use itertools::Itertools;
+
+/// Nodes that depend on each other in a loop, each one before the next and the last before
+/// the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N> {
+    pub nodes: Vec<N>,
+}
+
+impl<N: Display> Display for Cycle<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for node in self.nodes.iter() {
+            write!(f, "{} -> ", node)?;
+        }
+        match self.nodes.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Orders `nodes` so that for every `(before, after)` edge, `before` comes first. Edges
+/// touching a node outside of `nodes` are ignored. Whenever several nodes could come next,
+/// the one listed first in `nodes` does.
+pub fn topological_sort<N: Clone + Eq + Hash>(
+    nodes: &[N],
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> Result<Vec<N>, Cycle<N>> {
+    let index: HashMap<&N, usize> = nodes.iter().enumerate().map(|(i, n)| (n, i)).collect();
+    let mut successors = vec![vec![]; nodes.len()];
+    let mut predecessors = vec![vec![]; nodes.len()];
+    for (before, after) in edges {
+        if let (Some(&b), Some(&a)) = (index.get(&before), index.get(&after)) {
+            successors[b].push(a);
+            predecessors[a].push(b);
+        }
+    }
+
+    // Kahn's algorithm, taking the ready nodes by their index
+    let mut in_degree: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..nodes.len())
+        .filter(|&i| in_degree[i] == 0)
+        .map(Reverse)
+        .collect();
+    let mut sorted = Vec::with_capacity(nodes.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        sorted.push(node);
+        for &next in successors[node].iter() {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+    if sorted.len() == nodes.len() {
+        return Ok(sorted.into_iter().map(|i| nodes[i].clone()).collect());
+    }
+
+    // Every node left has a predecessor that is also left, so walking back through them
+    // must come back to a node already seen.
+    let mut walk = vec![];
+    let mut seen = vec![None; nodes.len()];
+    let mut node = (0..nodes.len()).find(|&i| in_degree[i] > 0).unwrap();
+    while seen[node].is_none() {
+        seen[node] = Some(walk.len());
+        walk.push(node);
+        node = *predecessors[node]
+            .iter()
+            .find(|&&p| in_degree[p] > 0)
+            .unwrap();
+    }
+    let mut cycle = walk.split_off(seen[node].unwrap());
+    cycle.reverse();
+    // start from the node listed first
+    let first = cycle.iter().position_min().unwrap();
+    cycle.rotate_left(first);
+    Err(Cycle {
+        nodes: cycle.into_iter().map(|i| nodes[i].clone()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_or_names_the_cycle() {
+        assert_eq!(
+            topological_sort(&[3, 1, 2], [(1, 2), (2, 3), (4, 1)]),
+            Ok(vec![1, 2, 3])
+        );
+        // 1 is ready again before 3
+        assert_eq!(topological_sort(&[1, 2, 3], [(2, 1)]), Ok(vec![2, 1, 3]));
+        let cycle = topological_sort(&[1, 2, 3, 4], [(1, 2), (2, 3), (3, 2), (3, 4)]).unwrap_err();
+        assert_eq!(cycle.to_string(), "2 -> 3 -> 2");
+    }
+}